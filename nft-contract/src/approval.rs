@@ -51,6 +51,13 @@ impl NftApproval for Contract {
             &env::predecessor_account_id() == &token.owner_id,
             "Only owner can approve transfer access",
         );
+        //marketplaces that skip the royalties can not be approved
+        self.internal_require_marketplace(
+            &account_id,
+            "Only allowlisted marketplaces can be approved while royalties are enforced",
+        );
+        //staked tokens are locked until they are unstaked
+        self.internal_require_not_staked(&token_id);
 
         //get the next approval ID if we need a new approval
         let approval_id: u64 = token.next_approval_id;
@@ -76,8 +83,8 @@ impl NftApproval for Contract {
         //insert the token back into the tokens_by_id collection
         self.tokens_by_id.insert(&token_id, &token);
 
-        //refund any excess storage attached by the user. If the user didn't attach enough, panic.
-        refund_deposit(storage_used);
+        //pay from the storage balance when possible, otherwise from the attached deposit
+        self.internal_pay_storage(&token.owner_id, storage_used);
 
         //if some message was passed into the function, we initiate a cross contract call on the
         //account we're giving access to.
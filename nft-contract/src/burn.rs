@@ -0,0 +1,100 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// What happens to the metadata of a burned token
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum BurnPolicy {
+    //the metadata stays registered and the token can not be minted again
+    Keep,
+    //the metadata is removed together with the token
+    Remove,
+}
+
+/**************/
+/* Token Burn */
+/**************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_burn_policy(&self) -> BurnPolicy {
+        self.burn_policy
+    }
+
+    #[payable]
+    pub fn ctrl_set_burn_policy(&mut self, policy: BurnPolicy) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can set the burn policy");
+        self.burn_policy = policy;
+    }
+
+    #[payable]
+    pub fn nft_burn(&mut self, token_id: TokenId) {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let sender_id = env::predecessor_account_id();
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+
+        //only the owner or an approved account can burn the token
+        require!(
+            sender_id == token.owner_id || token.approved_account_ids.contains_key(&sender_id),
+            "Only owner or approved account can burn the token",
+        );
+        //staked tokens are locked until they are unstaked
+        self.internal_require_not_staked(&token_id);
+        //removing the metadata would reorder the tokens that still have to be queued
+        self.internal_require_mint_queue_filled();
+
+        self.tokens_by_id.remove(&token_id);
+        self.internal_remove_token_from_owner(&token.owner_id, &token_id);
+        if self.burn_policy == BurnPolicy::Remove {
+            self.meta_data_by_id.remove(&token_id);
+            self.internal_remove_rarity(&token_id);
+        }
+
+        //clean up the state kept for the token
+        self.token_royalties.remove(&token_id);
+        self.sales_by_id.remove(&token_id);
+        self.gifts_by_id.remove(&token_id);
+        self.internal_clear_delegation(&token.owner_id, &token_id);
+        self.updated_at_by_id.remove(&token_id);
+        self.internal_unindex_traits(&token_id);
+
+        //refund the owner for the storage used up by the approved account IDs
+        refund_approved_account_ids(token.owner_id.clone(), &token.approved_account_ids);
+
+        // Default the authorized ID to be None for the logs.
+        let mut authorized_id = None;
+        //if an approved account burned the token, set the authorized ID equal to the sender
+        if sender_id != token.owner_id {
+            authorized_id = Some(sender_id.to_string());
+        }
+
+        // Construct the burn log as per the events standard.
+        let nft_burn_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard ("nft-1.0.0").
+            version: NFT_STANDARD_SPEC.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::NftBurn(vec![NftBurnLog {
+                // The optional authorized account ID that burned the token for the owner.
+                authorized_id,
+                // Owner of the token.
+                owner_id: token.owner_id.to_string(),
+                // Vector of token IDs that were burned.
+                token_ids: vec![token_id.to_string()],
+                // An optional memo to include.
+                memo: None,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&nft_burn_log.to_string());
+    }
+}
@@ -1,5 +1,16 @@
+use std::ops::Bound;
+
 use crate::*;
 
+//A page of tokens returned by the cursor views, the next page starts after next_cursor
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonTokenPage {
+    pub tokens: Vec<JsonToken>,
+    //last token ID of the page, none when there are no more tokens
+    pub next_cursor: Option<TokenId>,
+}
+
 pub trait NftEnumeration {
     //get the total supply of NFTs on the contract
     fn nft_total_supply(&self) -> U128;
@@ -131,8 +142,147 @@ impl NftMintEnumeration for Contract {
         let mut map = HashMap::new();
 
         for key in list {
-            map.insert(key.clone(), self.meta_data_by_id.get(&key.clone()).unwrap());
+            map.insert(key.clone(), self.internal_token_metadata(&key).unwrap());
         }
         map
     }
 }
+
+/*********************/
+/* Cursor Pagination */
+/*********************/
+
+#[near_bindgen]
+impl Contract {
+    /*
+        tokens in the order they were minted, starting after the cursor token.
+        unlike nft_tokens the pages stay the same when tokens are transferred or burned.
+    */
+    pub fn nft_tokens_by_cursor(
+        &self,
+        cursor: Option<TokenId>,
+        limit: Option<u64>,
+    ) -> JsonTokenPage {
+        //start after the cursor, or at the first minted token without one
+        let start = match cursor {
+            Some(cursor) => self.internal_mint_position(&cursor) + 1,
+            None => 0,
+        };
+        let limit = limit.unwrap_or(50) as usize;
+
+        //the mint queue never reorders, burned tokens are skipped. one extra token tells if there is a next page
+        let mut token_ids: Vec<TokenId> = (start..self.mint_queue_head)
+            .filter_map(|position| self.mint_queue.get(position))
+            .filter(|token_id| self.tokens_by_id.get(token_id).is_some())
+            .take(limit + 1)
+            .collect();
+
+        let mut next_cursor = None;
+        if token_ids.len() > limit {
+            token_ids.truncate(limit);
+            next_cursor = token_ids.last().cloned();
+        }
+
+        JsonTokenPage {
+            tokens: token_ids
+                .into_iter()
+                .map(|token_id| self.nft_token(token_id).unwrap())
+                .collect(),
+            next_cursor,
+        }
+    }
+
+    //tokens of an owner in the order they were minted, starting after the cursor token
+    pub fn nft_tokens_for_owner_by_cursor(
+        &self,
+        account_id: AccountId,
+        cursor: Option<TokenId>,
+        limit: Option<u64>,
+    ) -> JsonTokenPage {
+        let tokens_index = match self.owner_token_index.get(&account_id) {
+            Some(tokens_index) => tokens_index,
+            None => {
+                return JsonTokenPage {
+                    tokens: vec![],
+                    next_cursor: None,
+                }
+            }
+        };
+
+        //start after the cursor, or at the first token of the owner without one
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(self.internal_mint_position(&cursor)),
+            None => Bound::Included(0),
+        };
+        let limit = limit.unwrap_or(50) as usize;
+
+        //one extra token tells if there is a next page
+        let mut token_ids: Vec<TokenId> = tokens_index
+            .range((start, Bound::Unbounded))
+            .take(limit + 1)
+            .map(|(_, token_id)| token_id)
+            .collect();
+
+        let mut next_cursor = None;
+        if token_ids.len() > limit {
+            token_ids.truncate(limit);
+            next_cursor = token_ids.last().cloned();
+        }
+
+        JsonTokenPage {
+            tokens: token_ids
+                .into_iter()
+                .map(|token_id| self.nft_token(token_id).unwrap())
+                .collect(),
+            next_cursor,
+        }
+    }
+
+    /*
+        adds the minted tokens in the given range of the mint queue to the owner index.
+        tokens minted before the index existed are only paged by owner after this ran over them.
+        returns the number of tokens added.
+    */
+    #[payable]
+    pub fn ctrl_reindex_tokens(&mut self, from_index: Option<U128>, limit: Option<u64>) -> u64 {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can reindex tokens");
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0))) as u64;
+        let end = self
+            .mint_queue_head
+            .min(start.saturating_add(limit.unwrap_or(50)));
+
+        let mut count = 0;
+        for position in start..end {
+            let token_id = self.mint_queue.get(position).expect("Token not found");
+            if self.position_by_id.get(&token_id).is_some() {
+                continue;
+            }
+            self.position_by_id.insert(&token_id, &position);
+            //burned tokens only keep their position so they still work as a cursor
+            if let Some(token) = self.tokens_by_id.get(&token_id) {
+                self.internal_index_owner_token(&token.owner_id, position, &token_id);
+            }
+            count += 1;
+        }
+        count
+    }
+}
+
+impl Contract {
+    //position of a minted token in the mint queue
+    fn internal_mint_position(&self, token_id: &TokenId) -> u64 {
+        //tokens minted before the upgrade are looked up in the mint queue until a reindex ran over them
+        self.position_by_id
+            .get(token_id)
+            .or_else(|| {
+                (0..self.mint_queue_head)
+                    .find(|position| self.mint_queue.get(*position).as_ref() == Some(token_id))
+            })
+            .expect("Cursor token not found")
+    }
+}
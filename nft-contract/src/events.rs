@@ -1,9 +1,11 @@
+use std::collections::HashMap;
 use std::fmt;
 
 use near_sdk::serde::{Deserialize, Serialize};
 
 /// Enum that represents the data type of the EventLog.
-/// The enum can either be an NftMint or an NftTransfer.
+/// The nep171 events are NftMint, NftTransfer, NftBurn and the metadata updates, the remaining
+/// variants are emitted under the auras standard.
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "event", content = "data")]
 #[serde(rename_all = "snake_case")]
@@ -12,6 +14,40 @@ use near_sdk::serde::{Deserialize, Serialize};
 pub enum EventLogVariant {
     NftMint(Vec<NftMintLog>),
     NftTransfer(Vec<NftTransferLog>),
+    NftBurn(Vec<NftBurnLog>),
+    NftMetadataUpdate(Vec<NftMetadataUpdateLog>),
+    ContractMetadataUpdate(Vec<ContractMetadataUpdateLog>),
+    NftSale(Vec<NftSaleLog>),
+    SaleReject(Vec<SaleRejectLog>),
+
+    RoleGrant(Vec<RoleLog>),
+    RoleRevoke(Vec<RoleLog>),
+
+    OwnerPropose(Vec<OwnerLog>),
+    OwnerTransfer(Vec<OwnerLog>),
+    OwnerCancel(Vec<OwnerLog>),
+    RoyaltyAccountUpdate(Vec<RoyaltyAccountLog>),
+    RoyaltiesUpdate(Vec<RoyaltiesLog>),
+
+    TimelockQueue(Vec<TimelockLog>),
+    TimelockExecute(Vec<TimelockLog>),
+    TimelockCancel(Vec<TimelockLog>),
+
+    Withdrawal(Vec<WithdrawalLog>),
+
+    MarketplaceAdd(Vec<MarketplaceLog>),
+    MarketplaceRemove(Vec<MarketplaceLog>),
+
+    NftStake(Vec<StakeLog>),
+    NftUnstake(Vec<StakeLog>),
+    PointsClaim(Vec<PointsLog>),
+
+    SnapshotTake(Vec<SnapshotLog>),
+
+    ProposalCreate(Vec<ProposalLog>),
+    ProposalVote(Vec<VoteLog>),
+    VoteDelegate(Vec<DelegateLog>),
+    VoteUndelegate(Vec<DelegateLog>),
 }
 
 /// Interface to capture data about an event
@@ -77,3 +113,244 @@ pub struct NftTransferLog {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub memo: Option<String>,
 }
+
+/// An event log to capture token burning
+///
+/// Arguments
+/// * `authorized_id`: approved account to burn
+/// * `owner_id`: "owner.near"
+/// * `token_ids`: ["1", "12345abc"]
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftBurnLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authorized_id: Option<String>,
+
+    pub owner_id: String,
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a change of the token metadata
+///
+/// Arguments
+/// * `token_ids`: ["1", "12345abc"]
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftMetadataUpdateLog {
+    pub token_ids: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture a change of the contract metadata
+///
+/// Arguments
+/// * `memo`: optional message
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContractMetadataUpdateLog {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+}
+
+/// An event log to capture role changes
+///
+/// Arguments
+/// * `account_id`: "manager.near"
+/// * `role`: "whitelist_manager"
+/// * `sender_id`: "owner.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoleLog {
+    pub account_id: String,
+    pub role: String,
+    pub sender_id: String,
+}
+
+/// An event log to capture ownership proposals and transfers
+///
+/// Arguments
+/// * `old_owner_id`: "owner.near"
+/// * `new_owner_id`: "dao.sputnik-dao.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct OwnerLog {
+    pub old_owner_id: String,
+    pub new_owner_id: String,
+}
+
+/// An event log to capture a change of the royalty account
+///
+/// Arguments
+/// * `royalty_account_id`: "treasury.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltyAccountLog {
+    pub royalty_account_id: String,
+}
+
+/// An event log to capture actions moving through the timelock
+///
+/// Arguments
+/// * `action_id`: 3
+/// * `action`: "set_mint_info"
+/// * `eta`: block timestamp from which the action can be executed
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimelockLog {
+    pub action_id: u64,
+    pub action: String,
+    pub eta: u64,
+}
+
+/// An event log to capture treasury withdrawals
+///
+/// Arguments
+/// * `receiver_id`: "treasury.near"
+/// * `amount`: yoctoNEAR as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WithdrawalLog {
+    pub receiver_id: String,
+    pub amount: String,
+}
+
+/// An event log to capture a change of the collection royalties
+///
+/// Arguments
+/// * `royalties`: {"dao.near": 400, "artist.near": 200}
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RoyaltiesLog {
+    pub royalties: HashMap<String, u32>,
+}
+
+/// An event log to capture a secondary sale settled through nft_transfer_payout
+///
+/// Arguments
+/// * `token_id`: "1"
+/// * `seller_id`: "owner.near"
+/// * `buyer_id`: "buyer.near"
+/// * `market_id`: "market.near"
+/// * `price`: yoctoNEAR as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct NftSaleLog {
+    pub token_id: String,
+    pub seller_id: String,
+    pub buyer_id: String,
+    pub market_id: String,
+    pub price: String,
+}
+
+/// An event log to capture changes of the marketplace allowlist
+///
+/// Arguments
+/// * `account_id`: "market.near"
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct MarketplaceLog {
+    pub account_id: String,
+}
+
+/// An event log to capture a payout transfer rejected for its price
+///
+/// Arguments
+/// * `token_id`: "1"
+/// * `market_id`: "market.near"
+/// * `receiver_id`: "buyer.near"
+/// * `price`: yoctoNEAR as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleRejectLog {
+    pub token_id: String,
+    pub market_id: String,
+    pub receiver_id: String,
+    pub price: String,
+}
+
+/// An event log to capture tokens being staked or unstaked
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `token_ids`: ["1", "12345abc"]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakeLog {
+    pub owner_id: String,
+    pub token_ids: Vec<String>,
+}
+
+/// An event log to capture claimed staking points
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `points`: points as a string
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PointsLog {
+    pub owner_id: String,
+    pub points: String,
+}
+
+/// An event log to capture a snapshot of the holder balances
+///
+/// Arguments
+/// * `snapshot_id`: 1
+/// * `block_height`: block at which the balances were frozen
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SnapshotLog {
+    pub snapshot_id: u64,
+    pub block_height: u64,
+}
+
+/// An event log to capture a new proposal
+///
+/// Arguments
+/// * `proposal_id`: 1
+/// * `proposer_id`: "owner.near"
+/// * `deadline`: block timestamp after which no more votes are accepted
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalLog {
+    pub proposal_id: u64,
+    pub proposer_id: String,
+    pub deadline: u64,
+}
+
+/// An event log to capture votes on a proposal
+///
+/// Arguments
+/// * `proposal_id`: 1
+/// * `voter_id`: "owner.near"
+/// * `option`: index of the chosen option
+/// * `token_ids`: ["1", "12345abc"]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VoteLog {
+    pub proposal_id: u64,
+    pub voter_id: String,
+    pub option: u32,
+    pub token_ids: Vec<String>,
+}
+
+/// An event log to capture votes being delegated or undelegated
+///
+/// Arguments
+/// * `owner_id`: "owner.near"
+/// * `delegate_id`: "delegate.near"
+/// * `token_ids`: ["1", "12345abc"]
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegateLog {
+    pub owner_id: String,
+    pub delegate_id: String,
+    pub token_ids: Vec<String>,
+}
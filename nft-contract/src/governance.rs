@@ -0,0 +1,450 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Number of options a single proposal can offer
+pub const MAX_PROPOSAL_OPTIONS: usize = 16;
+
+/// A question put to the holders, every token casts one vote
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Proposal {
+    pub proposal_id: u64,
+    pub proposer_id: AccountId,
+    pub description: String,
+    pub options: Vec<String>,
+    //block timestamp after which no more votes are accepted
+    pub deadline: u64,
+    //votes of each option, in the order of the options
+    pub tallies: Vec<u64>,
+}
+
+/// Voting power an account lent to or received from other accounts
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Default)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DelegatedVotes {
+    //tokens of other owners delegated to the account
+    pub received: u64,
+    //tokens of the account delegated to other accounts
+    pub given: u64,
+}
+
+/// Outcome of a proposal as of the current votes
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ProposalResult {
+    pub proposal_id: u64,
+    //whether the deadline has passed and the result is final
+    pub closed: bool,
+    pub total_votes: u64,
+    //option with the most votes, none without votes or on a tie
+    pub winning_option: Option<u32>,
+}
+
+/*********************/
+/* Governance Config */
+/*********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_proposal_threshold(&self) -> u64 {
+        self.proposal_threshold
+    }
+
+    //tokens a holder needs to create proposals, admins can always create them
+    #[payable]
+    pub fn ctrl_set_proposal_threshold(&mut self, threshold: u64) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(
+            Role::Admin,
+            "Only owner or admin can set the proposal threshold",
+        );
+        self.proposal_threshold = threshold;
+    }
+}
+
+/*************/
+/* Proposals */
+/*************/
+
+#[near_bindgen]
+impl Contract {
+    //creates a proposal that can be voted on until the deadline, the caller pays for its storage
+    //from the storage balance or the attached deposit
+    #[payable]
+    pub fn nft_create_proposal(
+        &mut self,
+        description: String,
+        options: Vec<String>,
+        deadline: u64,
+    ) -> u64 {
+        let proposer_id = env::predecessor_account_id();
+        let balance = self
+            .tokens_per_owner
+            .get(&proposer_id)
+            .map_or(0, |tokens| tokens.len());
+        require!(
+            self.internal_has_role(&proposer_id, Role::Admin) || balance >= self.proposal_threshold,
+            "Not enough tokens to create a proposal",
+        );
+        require!(
+            options.len() >= 2 && options.len() <= MAX_PROPOSAL_OPTIONS,
+            format!(
+                "A proposal needs between 2 and {} options",
+                MAX_PROPOSAL_OPTIONS
+            ),
+        );
+        require!(
+            deadline > env::block_timestamp(),
+            "Deadline must be in the future",
+        );
+
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        let proposal = Proposal {
+            proposal_id: self.proposals.len() + 1,
+            proposer_id: proposer_id.clone(),
+            description,
+            tallies: vec![0; options.len()],
+            options,
+            deadline,
+        };
+        self.proposals.push(&proposal);
+
+        //pay from the storage balance when possible, otherwise from the attached deposit
+        self.internal_pay_storage(&proposer_id, env::storage_usage() - initial_storage_usage);
+
+        log_aura_event(EventLogVariant::ProposalCreate(vec![ProposalLog {
+            proposal_id: proposal.proposal_id,
+            proposer_id: proposer_id.to_string(),
+            deadline: proposal.deadline,
+        }]));
+
+        proposal.proposal_id
+    }
+
+    /*
+        votes for the option with every given token the caller owns or was delegated.
+        a token votes once per proposal, the vote stays with the token when it is transferred.
+        the voter pays for the stored votes from the storage balance or the attached deposit.
+    */
+    #[payable]
+    pub fn nft_vote(&mut self, proposal_id: u64, option: u32, token_ids: Vec<TokenId>) {
+        //storage so we need at least one yocto
+        require_at_least_one_yocto();
+
+        let mut proposal = self.internal_proposal(proposal_id);
+        require!(
+            env::block_timestamp() <= proposal.deadline,
+            "Proposal is closed",
+        );
+        require!(
+            (option as usize) < proposal.options.len(),
+            "Option not found",
+        );
+
+        let voter_id = env::predecessor_account_id();
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+        for token_id in token_ids.iter() {
+            let token = self.tokens_by_id.get(token_id).expect("Token not found");
+            //a delegated token votes through its delegate only
+            let token_voter_id = self.delegate_by_id.get(token_id).unwrap_or(token.owner_id);
+            require!(
+                token_voter_id == voter_id,
+                "Only owner or delegate can vote with the token",
+            );
+            require!(
+                self.votes_by_token
+                    .insert(&(proposal_id, token_id.clone()), &option)
+                    .is_none(),
+                "Token already voted on this proposal",
+            );
+        }
+        proposal.tallies[option as usize] += token_ids.len() as u64;
+        self.proposals.replace(proposal_id - 1, &proposal);
+
+        //pay from the storage balance when possible, otherwise from the attached deposit
+        self.internal_pay_storage(&voter_id, env::storage_usage() - initial_storage_usage);
+
+        log_aura_event(EventLogVariant::ProposalVote(vec![VoteLog {
+            proposal_id,
+            voter_id: voter_id.to_string(),
+            option,
+            token_ids,
+        }]));
+    }
+
+    pub fn nft_proposal(&self, proposal_id: u64) -> Option<Proposal> {
+        if proposal_id == 0 {
+            return None;
+        }
+        self.proposals.get(proposal_id - 1)
+    }
+
+    //proposals in the order they were created
+    pub fn nft_proposals(&self, from_index: Option<U128>, limit: Option<u64>) -> Vec<Proposal> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.proposals
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    pub fn nft_proposal_result(&self, proposal_id: u64) -> ProposalResult {
+        let proposal = self.internal_proposal(proposal_id);
+
+        let total_votes = proposal.tallies.iter().sum();
+        let max_votes = proposal.tallies.iter().cloned().max().unwrap_or(0);
+        //a single option has to lead, ties have no winner
+        let mut leaders = proposal
+            .tallies
+            .iter()
+            .enumerate()
+            .filter(|(_, votes)| **votes == max_votes);
+        let winning_option = match (leaders.next(), leaders.next()) {
+            (Some((option, _)), None) if max_votes > 0 => Some(option as u32),
+            _ => None,
+        };
+
+        ProposalResult {
+            proposal_id,
+            closed: env::block_timestamp() > proposal.deadline,
+            total_votes,
+            winning_option,
+        }
+    }
+
+    //option the token voted for on the proposal
+    pub fn nft_token_vote(&self, proposal_id: u64, token_id: TokenId) -> Option<u32> {
+        self.votes_by_token.get(&(proposal_id, token_id))
+    }
+}
+
+/*******************/
+/* Vote Delegation */
+/*******************/
+
+#[near_bindgen]
+impl Contract {
+    /*
+        lends the votes of the tokens to the delegate until they are undelegated or transferred.
+        the owner pays for the delegation from the storage balance or the attached deposit.
+    */
+    #[payable]
+    pub fn nft_delegate(&mut self, delegate_id: AccountId, token_ids: Vec<TokenId>) {
+        //storage so we need at least one yocto
+        require_at_least_one_yocto();
+
+        let owner_id = env::predecessor_account_id();
+        require!(
+            owner_id != delegate_id,
+            "The token owner and the delegate should be different",
+        );
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+        for token_id in token_ids.iter() {
+            let token = self.tokens_by_id.get(token_id).expect("Token not found");
+            require!(
+                token.owner_id == owner_id,
+                "Only owner can delegate the token",
+            );
+            //a token has a single delegate, a new one replaces the previous
+            self.internal_undelegate(&owner_id, token_id);
+            self.internal_delegate(&owner_id, &delegate_id, token_id);
+        }
+
+        //pay from the storage balance when possible, otherwise from the attached deposit
+        self.internal_pay_storage(
+            &owner_id,
+            env::storage_usage().saturating_sub(initial_storage_usage),
+        );
+
+        log_aura_event(EventLogVariant::VoteDelegate(vec![DelegateLog {
+            owner_id: owner_id.to_string(),
+            delegate_id: delegate_id.to_string(),
+            token_ids,
+        }]));
+    }
+
+    #[payable]
+    pub fn nft_undelegate(&mut self, token_ids: Vec<TokenId>) {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let owner_id = env::predecessor_account_id();
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+        let mut logs = vec![];
+        for token_id in token_ids.iter() {
+            let token = self.tokens_by_id.get(token_id).expect("Token not found");
+            require!(
+                token.owner_id == owner_id,
+                "Only owner can undelegate the token",
+            );
+            let delegate_id = self
+                .internal_undelegate(&owner_id, token_id)
+                .expect("Token is not delegated");
+            logs.push(DelegateLog {
+                owner_id: owner_id.to_string(),
+                delegate_id: delegate_id.to_string(),
+                token_ids: vec![token_id.clone()],
+            });
+        }
+
+        //the owner paid for the delegation, the released storage goes back to it
+        self.internal_release_storage(
+            &owner_id,
+            initial_storage_usage.saturating_sub(env::storage_usage()),
+        );
+
+        log_aura_event(EventLogVariant::VoteUndelegate(logs));
+    }
+
+    pub fn nft_token_delegate(&self, token_id: TokenId) -> Option<AccountId> {
+        self.delegate_by_id.get(&token_id)
+    }
+
+    //votes the account casts on a proposal, its own tokens that are not delegated plus the tokens delegated to it
+    pub fn nft_voting_power(&self, account_id: AccountId) -> u64 {
+        let held = self
+            .tokens_per_owner
+            .get(&account_id)
+            .map_or(0, |tokens| tokens.len());
+        let delegated = self.delegated_votes.get(&account_id).unwrap_or_default();
+        held - delegated.given + delegated.received
+    }
+
+    pub fn nft_delegated_votes(&self, account_id: AccountId) -> DelegatedVotes {
+        self.delegated_votes.get(&account_id).unwrap_or_default()
+    }
+
+    //owners that delegated tokens to the account and the number of tokens they delegated
+    pub fn nft_delegators(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, u64)> {
+        let delegators = match self.delegators_by_account.get(&account_id) {
+            Some(delegators) => delegators,
+            None => return vec![],
+        };
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        delegators
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    fn internal_proposal(&self, proposal_id: u64) -> Proposal {
+        self.nft_proposal(proposal_id).expect("Proposal not found")
+    }
+}
+
+impl Contract {
+    fn internal_delegate(
+        &mut self,
+        owner_id: &AccountId,
+        delegate_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        self.delegate_by_id.insert(token_id, delegate_id);
+
+        let mut delegators = self
+            .delegators_by_account
+            .get(delegate_id)
+            .unwrap_or_else(|| {
+                UnorderedMap::new(
+                    StorageKey::DelegatorsInner {
+                        //we get a new unique prefix for the collection
+                        account_id_hash: hash_account_id(delegate_id),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
+            });
+        let count = delegators.get(owner_id).unwrap_or(0);
+        delegators.insert(owner_id, &(count + 1));
+        self.delegators_by_account.insert(delegate_id, &delegators);
+
+        self.internal_update_delegated_votes(delegate_id, |votes| votes.received += 1);
+        self.internal_update_delegated_votes(owner_id, |votes| votes.given += 1);
+    }
+
+    //removes the delegation of the token and returns the previous delegate
+    fn internal_undelegate(
+        &mut self,
+        owner_id: &AccountId,
+        token_id: &TokenId,
+    ) -> Option<AccountId> {
+        let delegate_id = self.delegate_by_id.remove(token_id)?;
+
+        let mut delegators = self
+            .delegators_by_account
+            .get(&delegate_id)
+            .expect("Delegators not found");
+        let count = delegators.get(owner_id).unwrap_or(0);
+        if count > 1 {
+            delegators.insert(owner_id, &(count - 1));
+        } else {
+            delegators.remove(owner_id);
+        }
+        //drop the map of accounts without delegators, like tokens_per_owner does
+        if delegators.is_empty() {
+            self.delegators_by_account.remove(&delegate_id);
+        } else {
+            self.delegators_by_account.insert(&delegate_id, &delegators);
+        }
+
+        self.internal_update_delegated_votes(&delegate_id, |votes| votes.received -= 1);
+        self.internal_update_delegated_votes(owner_id, |votes| votes.given -= 1);
+
+        Some(delegate_id)
+    }
+
+    fn internal_update_delegated_votes<F>(&mut self, account_id: &AccountId, update: F)
+    where
+        F: FnOnce(&mut DelegatedVotes),
+    {
+        let mut votes = self.delegated_votes.get(account_id).unwrap_or_default();
+        update(&mut votes);
+        if votes.received == 0 && votes.given == 0 {
+            self.delegated_votes.remove(account_id);
+        } else {
+            self.delegated_votes.insert(account_id, &votes);
+        }
+    }
+
+    //the delegation only applies to the owner that set it, it is cleared when the token moves
+    pub(crate) fn internal_clear_delegation(&mut self, owner_id: &AccountId, token_id: &TokenId) {
+        let initial_storage_usage = env::storage_usage();
+        if let Some(delegate_id) = self.internal_undelegate(owner_id, token_id) {
+            self.internal_release_storage(
+                owner_id,
+                initial_storage_usage.saturating_sub(env::storage_usage()),
+            );
+            log_aura_event(EventLogVariant::VoteUndelegate(vec![DelegateLog {
+                owner_id: owner_id.to_string(),
+                delegate_id: delegate_id.to_string(),
+                token_ids: vec![token_id.clone()],
+            }]));
+        }
+    }
+}
@@ -4,7 +4,9 @@ use std::mem::size_of;
 
 //convert the royalty percentage and amount to pay into a payout (U128)
 pub(crate) fn royalty_to_payout(royalty_percentage: u32, amount_to_pay: Balance) -> U128 {
-    U128(royalty_percentage as u128 * amount_to_pay / 10_000u128)
+    //split the amount so large balances can not overflow, the result is still rounded down
+    let royalty = royalty_percentage as u128;
+    U128(amount_to_pay / 10_000u128 * royalty + amount_to_pay % 10_000u128 * royalty / 10_000u128)
 }
 
 //calculate how many bytes the account ID is taking up
@@ -47,6 +49,15 @@ pub(crate) fn hash_account_id(account_id: &AccountId) -> CryptoHash {
     hash
 }
 
+//used to generate a unique prefix for the token set of a trait value
+pub(crate) fn hash_token_trait(token_trait: &TokenTrait) -> CryptoHash {
+    //get the default hash
+    let mut hash = CryptoHash::default();
+    //we hash the serialized trait pair and return it
+    hash.copy_from_slice(&env::sha256(&token_trait.try_to_vec().unwrap()));
+    hash
+}
+
 //used to make sure the user attached exactly 1 yoctoNEAR
 pub(crate) fn require_one_yocto() {
     require!(
@@ -85,6 +96,19 @@ pub(crate) fn refund_deposit(storage_used: u64) {
     }
 }
 
+//log an event that is specific to this contract under the auras standard
+pub(crate) fn log_aura_event(event: EventLogVariant) {
+    // Construct the log, these events are not part of nep171 so they use the auras standard.
+    let aura_log: EventLog = EventLog {
+        standard: AURA_STANDARD_NAME.to_string(),
+        version: AURA_STANDARD_SPEC.to_string(),
+        event,
+    };
+
+    // Log the serialized json.
+    env::log_str(&aura_log.to_string());
+}
+
 impl Contract {
     //add a token to the set of tokens an owner has
     pub(crate) fn internal_add_token_to_owner(
@@ -92,6 +116,9 @@ impl Contract {
         account_id: &AccountId,
         token_id: &TokenId,
     ) {
+        //keep the balance of the latest snapshot before it changes
+        self.internal_checkpoint_balance(account_id);
+
         //get the set of tokens for the given account
         let mut tokens_set = self.tokens_per_owner.get(account_id).unwrap_or_else(|| {
             //if the account doesn't have any tokens, we create a new unordered set
@@ -110,6 +137,35 @@ impl Contract {
 
         //we insert that set for the given account ID.
         self.tokens_per_owner.insert(account_id, &tokens_set);
+        //a returning holder is listed with the current holders again
+        if tokens_set.len() == 1 {
+            self.past_holders.remove(account_id);
+        }
+        //keep the ordered index used by cursor pagination, tokens that were not indexed yet are added by a reindex
+        if let Some(position) = self.position_by_id.get(token_id) {
+            self.internal_index_owner_token(account_id, position, token_id);
+        }
+    }
+
+    //add a token to the ordered index of an owner
+    pub(crate) fn internal_index_owner_token(
+        &mut self,
+        account_id: &AccountId,
+        position: u64,
+        token_id: &TokenId,
+    ) {
+        let mut tokens_index = self.owner_token_index.get(account_id).unwrap_or_else(|| {
+            TreeMap::new(
+                StorageKey::OwnerTokenIndexInner {
+                    //we get a new unique prefix for the collection
+                    account_id_hash: hash_account_id(account_id),
+                }
+                .try_to_vec()
+                .unwrap(),
+            )
+        });
+        tokens_index.insert(&position, token_id);
+        self.owner_token_index.insert(account_id, &tokens_index);
     }
 
     //remove a token from an owner (internal method and can't be called directly via CLI).
@@ -118,6 +174,9 @@ impl Contract {
         account_id: &AccountId,
         token_id: &TokenId,
     ) {
+        //keep the balance of the latest snapshot before it changes
+        self.internal_checkpoint_balance(account_id);
+
         //we get the set of tokens that the owner has
         let mut tokens_set = self
             .tokens_per_owner
@@ -131,24 +190,41 @@ impl Contract {
         //if the token set is now empty, we remove the owner from the tokens_per_owner collection
         if tokens_set.is_empty() {
             self.tokens_per_owner.remove(account_id);
+            //the account can still be listed as a holder of earlier snapshots
+            if !self.snapshots.is_empty() {
+                self.past_holders.insert(account_id);
+            }
         } else {
             //if the token set is not empty, we simply insert it back for the account ID.
             self.tokens_per_owner.insert(account_id, &tokens_set);
         }
+        if let Some(position) = self.position_by_id.get(token_id) {
+            if let Some(mut tokens_index) = self.owner_token_index.get(account_id) {
+                tokens_index.remove(&position);
+                //drop the index of owners without tokens, like tokens_per_owner does
+                if tokens_index.is_empty() {
+                    self.owner_token_index.remove(account_id);
+                } else {
+                    self.owner_token_index.insert(account_id, &tokens_index);
+                }
+            }
+        }
     }
 
     //transfers the NFT to the receiver_id (internal method and can't be called directly via CLI).
-    pub(crate) fn internal_transfer(
+    //moves the token to the receiver without logging, the caller logs the transfer event
+    pub(crate) fn internal_move_token(
         &mut self,
         sender_id: &AccountId,
         receiver_id: &AccountId,
         token_id: &TokenId,
         //we introduce an approval ID so that people with that approval ID can transfer the token
         approval_id: Option<u64>,
-        memo: Option<String>,
     ) -> Token {
         //get the token object by passing in the token_id
         let token = self.tokens_by_id.get(token_id).expect("Token not found");
+        //staked tokens are locked until they are unstaked
+        self.internal_require_not_staked(token_id);
 
         //if the sender doesn't equal the owner, we check if the sender is in the approval list
         if sender_id != &token.owner_id {
@@ -194,6 +270,25 @@ impl Contract {
         };
         //insert that new token into the tokens_by_id, replacing the old entry
         self.tokens_by_id.insert(token_id, &new_token);
+        //a gift flag only applies to the owner that set it
+        self.gifts_by_id.remove(token_id);
+        self.internal_clear_delegation(&token.owner_id, token_id);
+
+        //return the preivous token object that was transferred.
+        token
+    }
+
+    pub(crate) fn internal_transfer(
+        &mut self,
+        sender_id: &AccountId,
+        receiver_id: &AccountId,
+        token_id: &TokenId,
+        //we introduce an approval ID so that people with that approval ID can transfer the token
+        approval_id: Option<u64>,
+        memo: Option<String>,
+    ) -> Token {
+        //move the token to the receiver and get the previous token object back
+        let token = self.internal_move_token(sender_id, receiver_id, token_id, approval_id);
 
         //if there was some memo attached, we log it.
         if let Some(memo) = memo.as_ref() {
@@ -1,41 +1,80 @@
 use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
-use near_sdk::collections::{LazyOption, UnorderedMap, UnorderedSet};
+use near_sdk::collections::{LazyOption, LookupMap, TreeMap, UnorderedMap, UnorderedSet, Vector};
 use near_sdk::json_types::U128;
 use near_sdk::serde::{Deserialize, Serialize};
 use near_sdk::{
-    env, near_bindgen, require, AccountId, Balance, CryptoHash, PanicOnDefault, Promise,
-    PromiseOrValue, ONE_NEAR,
+    env, near_bindgen, AccountId, Balance, CryptoHash, PanicOnDefault, Promise, PromiseOrValue,
+    ONE_NEAR,
 };
 use std::collections::HashMap;
 
 pub use crate::approval::*;
+pub use crate::burn::*;
 pub use crate::events::*;
+pub use crate::governance::*;
 use crate::internal::*;
 pub use crate::metadata::*;
 pub use crate::mint::*;
 pub use crate::nft_core::*;
+pub use crate::rarity::*;
+pub use crate::roles::*;
 pub use crate::royalty::*;
+pub use crate::sales::*;
+pub use crate::snapshot::*;
+pub use crate::staking::*;
+pub use crate::storage::*;
+pub use crate::timelock::*;
+pub use crate::traits::*;
+pub use crate::treasury::*;
+pub use crate::upgrade::*;
 
 mod approval;
+mod burn;
 mod enumeration;
 mod events;
+mod governance;
 mod internal;
+mod market;
 mod metadata;
 mod mint;
 mod nft_core;
+mod ownership;
+mod rarity;
+mod roles;
 mod royalty;
+mod sales;
+mod snapshot;
+mod staking;
+mod storage;
+mod timelock;
+mod traits;
+mod treasury;
+mod upgrade;
 
 /// This is the version of the standard implementation
-pub const NFT_STANDARD_SPEC: &str = "1.0.0";
+pub const NFT_STANDARD_SPEC: &str = "1.1.0";
 /// This is the name of the NFT standard we're using
 pub const NFT_STANDARD_NAME: &str = "nep171";
+/// This is the version of the contract specific events
+pub const AURA_STANDARD_SPEC: &str = "1.0.0";
+/// This is the name used for the contract specific events
+pub const AURA_STANDARD_NAME: &str = "auras";
 
 #[near_bindgen]
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
+    //version of the state layout, used by migrate after an upgrade
+    pub state_version: u16,
+
     //contract owner
     pub owner_id: AccountId,
 
+    //account proposed as the next owner, it has to accept before taking over
+    pub pending_owner_id: Option<AccountId>,
+
+    //account that receives the royalties, defaults to the owner when not set
+    pub royalty_account_id: Option<AccountId>,
+
     //keeps track of the metadata for the contract
     pub metadata: LazyOption<ContractMetadata>,
 
@@ -49,13 +88,121 @@ pub struct Contract {
     pub tokens_by_id: UnorderedMap<TokenId, Token>,
 
     //keeps track of the token metadata for a given token ID
-    pub meta_data_by_id: UnorderedMap<TokenId, TokenMetadata>,
+    pub meta_data_by_id: UnorderedMap<TokenId, VersionedTokenMetadata>,
 
     //keeps track of all the token IDs for a given account
     pub tokens_per_owner: UnorderedMap<AccountId, UnorderedSet<TokenId>>,
+
+    //position of each minted token in the mint queue, used as cursor for pagination
+    pub position_by_id: LookupMap<TokenId, u64>,
+    //minted token IDs of every owner ordered by their position, used for cursor pagination
+    pub owner_token_index: LookupMap<AccountId, TreeMap<u64, TokenId>>,
+
+    //registered token IDs in the order they are minted
+    pub mint_queue: Vector<TokenId>,
+    //position in the mint queue of the next token to mint
+    pub mint_queue_head: u64,
+    //registered tokens of the first version that are not in the mint queue yet
+    pub mint_queue_pending: u64,
+
+    //keeps track of the privileged roles granted to accounts
+    pub roles: UnorderedMap<AccountId, Vec<Role>>,
+
+    //minting can be halted by a pauser
+    pub mint_paused: bool,
+
+    //delay that sensitive actions have to wait in the queue
+    pub timelock: LazyOption<TimelockConfig>,
+
+    //keeps track of the queued actions by their ID
+    pub timelock_actions: UnorderedMap<u64, PendingAction>,
+
+    //the next timelock action ID to give out
+    pub timelock_next_id: u64,
+
+    //direct withdrawals counted against the timelock threshold
+    pub withdrawal_window: WithdrawalWindow,
+
+    //keeps track of the revenue and withdrawals
+    pub treasury: Treasury,
+
+    //collection royalties in basis points, the default royalty applies when not set
+    pub royalties: LazyOption<HashMap<AccountId, u32>>,
+    //creator royalties of guest artists, paid on top of the collection royalties
+    pub token_royalties: UnorderedMap<TokenId, HashMap<AccountId, u32>>,
+    //highest creator royalty total of a single token in basis points
+    pub max_token_royalty: u32,
+
+    //bounded history of the secondary sales of each token
+    pub sales_by_id: UnorderedMap<TokenId, Vec<SaleRecord>>,
+    //running totals of the secondary sales
+    pub sale_stats: SaleStats,
+
+    //approvals and transfers by approved accounts are restricted when enabled
+    pub royalty_enforced: bool,
+    //marketplace contracts that can be approved while royalties are enforced
+    pub marketplaces: UnorderedSet<AccountId>,
+    //lowest balance accepted by nft_transfer_payout
+    pub min_sale_price: U128,
+    //receivers that the token owner allowed to get the token below the minimum price
+    pub gifts_by_id: LookupMap<TokenId, AccountId>,
+
+    //whether the metadata of burned tokens is kept or removed
+    pub burn_policy: BurnPolicy,
+
+    //points earned per second by staked tokens
+    pub staking: LazyOption<StakingConfig>,
+    //tokens locked by their owner to earn points
+    pub stakes_by_id: UnorderedMap<TokenId, Stake>,
+    //claimed points of each account
+    pub points_by_owner: LookupMap<AccountId, u128>,
+
+    //prepaid NEP-145 storage balance of each account
+    pub storage_deposits: LookupMap<AccountId, Balance>,
+
+    //last time the metadata of an evolved token changed
+    pub updated_at_by_id: LookupMap<TokenId, u64>,
+
+    //typed trait pairs parsed from the attributes string of each minted token, rebuilt when the attributes change
+    pub traits_by_id: LookupMap<TokenId, Vec<TokenTrait>>,
+    //keeps track of the minted token IDs for a given trait value
+    pub tokens_by_trait: LookupMap<TokenTrait, UnorderedSet<TokenId>>,
+    //number of minted tokens for every trait type and value
+    pub trait_counts: UnorderedMap<String, HashMap<String, u64>>,
+
+    //progress of the paged rarity computation
+    pub rarity_progress: RarityProgress,
+    //number of registered tokens for every trait type and value
+    pub trait_frequencies: UnorderedMap<String, HashMap<String, u64>>,
+    //score and rank of each registered token
+    pub rarity_by_id: LookupMap<TokenId, Rarity>,
+    //registered tokens ordered from the rarest to the most common
+    pub rarity_ranking: TreeMap<(u64, TokenId), ()>,
+
+    //snapshots of the holder balances, the snapshot ID is the position plus one
+    pub snapshots: Vector<Snapshot>,
+    //balances saved on the first change after a snapshot, as (snapshot ID, balance)
+    pub balance_checkpoints: LookupMap<AccountId, Vec<(u64, u64)>>,
+    //accounts that sold all their tokens after a snapshot was taken
+    pub past_holders: UnorderedSet<AccountId>,
+
+    //proposals put to the holders, the proposal ID is the position plus one
+    pub proposals: Vector<Proposal>,
+    //option each token voted for, by (proposal ID, token ID)
+    pub votes_by_token: LookupMap<(u64, TokenId), u32>,
+    //tokens a holder needs to create a proposal
+    pub proposal_threshold: u64,
+
+    //account the votes of a token are delegated to
+    pub delegate_by_id: LookupMap<TokenId, AccountId>,
+    //owners that delegated to an account and their number of delegated tokens
+    pub delegators_by_account: LookupMap<AccountId, UnorderedMap<AccountId, u64>>,
+    //delegated tokens received and given by each account
+    pub delegated_votes: LookupMap<AccountId, DelegatedVotes>,
 }
 
 /// Helper structure for keys of the persistent collections.
+/// The position of a variant is its storage prefix, new keys are only appended.
 #[derive(BorshSerialize)]
 pub enum StorageKey {
     ContractMetadata,
@@ -67,6 +214,54 @@ pub enum StorageKey {
 
     TokensPerOwner,
     TokenPerOwnerInner { account_id_hash: CryptoHash },
+
+    Roles,
+
+    Timelock,
+    TimelockActions,
+
+    Royalties,
+    TokenRoyalties,
+
+    SalesById,
+
+    Marketplaces,
+    GiftsById,
+
+    Staking,
+    StakesById,
+    PointsByOwner,
+
+    StorageDeposits,
+
+    UpdatedAtById,
+
+    TraitsById,
+    TokensByTrait,
+    TokensByTraitInner { trait_hash: CryptoHash },
+    TraitCounts,
+
+    TraitFrequencies,
+    RarityById,
+    RarityRanking,
+
+    MintQueue,
+
+    PositionById,
+    OwnerTokenIndex,
+    OwnerTokenIndexInner { account_id_hash: CryptoHash },
+
+    Snapshots,
+    BalanceCheckpoints,
+    PastHolders,
+
+    Proposals,
+    VotesByToken,
+
+    DelegateById,
+    DelegatorsByAccount,
+    DelegatorsInner { account_id_hash: CryptoHash },
+    DelegatedVotes,
 }
 
 /**************/
@@ -108,8 +303,12 @@ impl Contract {
     pub fn ctrl_init(owner_id: AccountId, info: MintInfo, metadata: ContractMetadata) -> Self {
         // Initialize data and return it
         Self {
+            state_version: CONTRACT_STATE_VERSION,
+
             //Set the contract data fields equal to the passed in owner_id.
             owner_id,
+            pending_owner_id: None,
+            royalty_account_id: None,
             metadata: LazyOption::new(
                 StorageKey::ContractMetadata.try_to_vec().unwrap(),
                 Some(&metadata),
@@ -124,13 +323,91 @@ impl Contract {
 
             meta_data_by_id: UnorderedMap::new(StorageKey::MetaDataById.try_to_vec().unwrap()),
             mint_state_list: UnorderedMap::new(StorageKey::MintStateList.try_to_vec().unwrap()),
+
+            position_by_id: LookupMap::new(StorageKey::PositionById.try_to_vec().unwrap()),
+            owner_token_index: LookupMap::new(StorageKey::OwnerTokenIndex.try_to_vec().unwrap()),
+
+            mint_queue: Vector::new(StorageKey::MintQueue.try_to_vec().unwrap()),
+            mint_queue_head: 0,
+            mint_queue_pending: 0,
+
+            roles: UnorderedMap::new(StorageKey::Roles.try_to_vec().unwrap()),
+            mint_paused: false,
+
+            timelock: LazyOption::new(
+                StorageKey::Timelock.try_to_vec().unwrap(),
+                Some(&TimelockConfig {
+                    delay: 0,
+                    withdrawal_threshold: U128(0),
+                }),
+            ),
+            timelock_actions: UnorderedMap::new(StorageKey::TimelockActions.try_to_vec().unwrap()),
+            timelock_next_id: 0,
+            withdrawal_window: WithdrawalWindow::default(),
+
+            treasury: Treasury::default(),
+
+            royalties: LazyOption::new(StorageKey::Royalties.try_to_vec().unwrap(), None),
+            token_royalties: UnorderedMap::new(StorageKey::TokenRoyalties.try_to_vec().unwrap()),
+            max_token_royalty: 0,
+
+            sales_by_id: UnorderedMap::new(StorageKey::SalesById.try_to_vec().unwrap()),
+            sale_stats: SaleStats::default(),
+
+            royalty_enforced: false,
+            marketplaces: UnorderedSet::new(StorageKey::Marketplaces.try_to_vec().unwrap()),
+            min_sale_price: U128(0),
+            gifts_by_id: LookupMap::new(StorageKey::GiftsById.try_to_vec().unwrap()),
+
+            burn_policy: BurnPolicy::Keep,
+
+            staking: LazyOption::new(
+                StorageKey::Staking.try_to_vec().unwrap(),
+                Some(&StakingConfig {
+                    token_rate: 0,
+                    trait_rates: HashMap::new(),
+                }),
+            ),
+            stakes_by_id: UnorderedMap::new(StorageKey::StakesById.try_to_vec().unwrap()),
+            points_by_owner: LookupMap::new(StorageKey::PointsByOwner.try_to_vec().unwrap()),
+
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+
+            updated_at_by_id: LookupMap::new(StorageKey::UpdatedAtById.try_to_vec().unwrap()),
+
+            traits_by_id: LookupMap::new(StorageKey::TraitsById.try_to_vec().unwrap()),
+            tokens_by_trait: LookupMap::new(StorageKey::TokensByTrait.try_to_vec().unwrap()),
+            trait_counts: UnorderedMap::new(StorageKey::TraitCounts.try_to_vec().unwrap()),
+
+            rarity_progress: RarityProgress::default(),
+            trait_frequencies: UnorderedMap::new(
+                StorageKey::TraitFrequencies.try_to_vec().unwrap(),
+            ),
+            rarity_by_id: LookupMap::new(StorageKey::RarityById.try_to_vec().unwrap()),
+            rarity_ranking: TreeMap::new(StorageKey::RarityRanking.try_to_vec().unwrap()),
+
+            snapshots: Vector::new(StorageKey::Snapshots.try_to_vec().unwrap()),
+            balance_checkpoints: LookupMap::new(
+                StorageKey::BalanceCheckpoints.try_to_vec().unwrap(),
+            ),
+            past_holders: UnorderedSet::new(StorageKey::PastHolders.try_to_vec().unwrap()),
+
+            proposals: Vector::new(StorageKey::Proposals.try_to_vec().unwrap()),
+            votes_by_token: LookupMap::new(StorageKey::VotesByToken.try_to_vec().unwrap()),
+            proposal_threshold: 1,
+
+            delegate_by_id: LookupMap::new(StorageKey::DelegateById.try_to_vec().unwrap()),
+            delegators_by_account: LookupMap::new(
+                StorageKey::DelegatorsByAccount.try_to_vec().unwrap(),
+            ),
+            delegated_votes: LookupMap::new(StorageKey::DelegatedVotes.try_to_vec().unwrap()),
         }
     }
 }
 
-/*******************/
-/* Near Withdrawal */
-/*******************/
+/*****************/
+/* Contract Info */
+/*****************/
 
 #[near_bindgen]
 impl Contract {
@@ -145,18 +422,6 @@ impl Contract {
     pub fn ctrl_storage_usage(&self) -> u64 {
         env::storage_usage()
     }
-
-    #[payable]
-    pub fn ctrl_withdrawal(&mut self, amount: U128) {
-        //require that the owner attached 1 yoctoNEAR for security reasons
-        require_one_yocto();
-        //require the the sender is the owner of the contract
-        require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can withdrawal funds",
-        );
-        Promise::new(env::predecessor_account_id()).transfer(u128::from(amount));
-    }
 }
 
 /*******************************/
@@ -0,0 +1,189 @@
+use near_sdk::require;
+
+use crate::*;
+
+/***********************/
+/* Royalty Enforcement */
+/***********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_royalty_enforced(&self) -> bool {
+        self.royalty_enforced
+    }
+
+    /*
+        while royalties are enforced only allowlisted marketplaces can be approved and
+        approved accounts have to transfer through nft_transfer_payout.
+    */
+    #[payable]
+    pub fn ctrl_set_royalty_enforced(&mut self, enforced: bool) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(
+            Role::Admin,
+            "Only owner or admin can set royalty enforcement",
+        );
+        self.royalty_enforced = enforced;
+    }
+}
+
+/**********************/
+/* Minimum Sale Price */
+/**********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_min_sale_price(&self) -> U128 {
+        self.min_sale_price
+    }
+
+    //sales through nft_transfer_payout below this price are rejected, 0 disables the check
+    #[payable]
+    pub fn ctrl_set_min_sale_price(&mut self, price: U128) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(
+            Role::Admin,
+            "Only owner or admin can set the minimum sale price",
+        );
+        self.min_sale_price = price;
+    }
+
+    pub fn nft_gift_receiver(&self, token_id: TokenId) -> Option<AccountId> {
+        self.gifts_by_id.get(&token_id)
+    }
+
+    //lets the next payout transfer of the token to the receiver go below the minimum price
+    #[payable]
+    pub fn nft_flag_gift(&mut self, token_id: TokenId, receiver_id: Option<AccountId>) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let token = self.tokens_by_id.get(&token_id).expect("Token not found");
+        require!(
+            env::predecessor_account_id() == token.owner_id,
+            "Only owner can flag a gift",
+        );
+
+        match receiver_id {
+            Some(receiver_id) => {
+                self.gifts_by_id.insert(&token_id, &receiver_id);
+            }
+            None => {
+                self.gifts_by_id.remove(&token_id);
+            }
+        }
+    }
+}
+
+/*************************/
+/* Marketplace Allowlist */
+/*************************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_marketplaces(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<AccountId> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.marketplaces
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+
+    pub fn ctrl_is_marketplace(&self, account_id: AccountId) -> bool {
+        self.marketplaces.contains(&account_id)
+    }
+
+    #[payable]
+    pub fn ctrl_add_marketplace(&mut self, account_id: AccountId) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can manage marketplaces");
+
+        if self.marketplaces.insert(&account_id) {
+            log_aura_event(EventLogVariant::MarketplaceAdd(vec![MarketplaceLog {
+                account_id: account_id.to_string(),
+            }]));
+        }
+    }
+
+    #[payable]
+    pub fn ctrl_remove_marketplace(&mut self, account_id: AccountId) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can manage marketplaces");
+
+        if self.marketplaces.remove(&account_id) {
+            log_aura_event(EventLogVariant::MarketplaceRemove(vec![MarketplaceLog {
+                account_id: account_id.to_string(),
+            }]));
+        }
+    }
+}
+
+impl Contract {
+    //only allowlisted marketplaces can be approved while royalties are enforced
+    pub(crate) fn internal_require_marketplace(&self, account_id: &AccountId, message: &str) {
+        require!(
+            !self.royalty_enforced || self.marketplaces.contains(account_id),
+            message,
+        );
+    }
+
+    //reject sales below the minimum price unless the owner flagged the transfer as a gift, returns false for a gift
+    pub(crate) fn internal_require_sale_price(
+        &self,
+        token_id: &TokenId,
+        receiver_id: &AccountId,
+        price: Balance,
+    ) -> bool {
+        if price >= self.min_sale_price.0 {
+            return true;
+        }
+        if self.gifts_by_id.get(token_id).as_ref() == Some(receiver_id) {
+            return false;
+        }
+
+        //the log stays in the receipt outcome so misbehaving marketplaces can be found
+        log_aura_event(EventLogVariant::SaleReject(vec![SaleRejectLog {
+            token_id: token_id.to_string(),
+            market_id: env::predecessor_account_id().to_string(),
+            receiver_id: receiver_id.to_string(),
+            price: price.to_string(),
+        }]));
+        env::panic_str(&format!(
+            "Sale price is below the minimum of {} yoctoNEAR",
+            self.min_sale_price.0
+        ));
+    }
+
+    //approved accounts can not route around the payout while royalties are enforced
+    pub(crate) fn internal_require_owner_transfer(
+        &self,
+        sender_id: &AccountId,
+        token_id: &TokenId,
+    ) {
+        if !self.royalty_enforced {
+            return;
+        }
+        let token = self.tokens_by_id.get(token_id).expect("Token not found");
+        require!(
+            sender_id == &token.owner_id,
+            "Approved accounts must transfer through nft_transfer_payout",
+        );
+    }
+}
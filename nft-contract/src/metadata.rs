@@ -1,4 +1,5 @@
 use crate::*;
+use near_sdk::require;
 pub type TokenId = String;
 
 //defines the payout type we'll be returning as a part of the royalty standards.
@@ -41,7 +42,91 @@ pub struct TokenMetadata {
     pub title: String,      // ex. "Arch Nemesis: Mail Carrier" or "Parcel #5055"
     pub media: String, // URL to associated media, preferably to decentralized, content-addressed storage
     pub media_hash: String, // Base64-encoded sha256 hash of content referenced by the `media` field. Required if `media` is included.
-    pub attributes: String, // Token attributes as a stringified JSON object.
+    pub attributes: String, // Token attributes as a stringified JSON object, the trait index is parsed from it.
+    #[serde(default)]
+    pub description: Option<String>, // free-form description, the collection description is used when not set
+    #[serde(default)]
+    pub reference: Option<String>, // URL to an off-chain JSON file with more info.
+    #[serde(default)]
+    pub reference_hash: Option<String>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
+}
+
+//token metadata as it was stored before the optional fields were added
+#[derive(Clone, BorshDeserialize, BorshSerialize)]
+pub struct TokenMetadataV1 {
+    pub title: String,
+    pub media: String,
+    pub media_hash: String,
+    pub attributes: String,
+}
+
+/*
+    token metadata as it is stored in meta_data_by_id.
+    the first layout was stored without a version tag and starts with the length of the title.
+    newer layouts start with a length that no title can have, followed by the version.
+*/
+#[derive(Clone)]
+pub enum VersionedTokenMetadata {
+    V1(TokenMetadataV1),
+    V2(TokenMetadata),
+}
+
+const TOKEN_METADATA_TAG: [u8; 4] = [0xFF; 4];
+
+impl BorshSerialize for VersionedTokenMetadata {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        match self {
+            VersionedTokenMetadata::V1(metadata) => BorshSerialize::serialize(metadata, writer),
+            VersionedTokenMetadata::V2(metadata) => {
+                writer.write_all(&TOKEN_METADATA_TAG)?;
+                BorshSerialize::serialize(&2u8, writer)?;
+                BorshSerialize::serialize(metadata, writer)
+            }
+        }
+    }
+}
+
+impl BorshDeserialize for VersionedTokenMetadata {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        if !buf.starts_with(&TOKEN_METADATA_TAG) {
+            return Ok(VersionedTokenMetadata::V1(TokenMetadataV1::deserialize(
+                buf,
+            )?));
+        }
+        *buf = &buf[TOKEN_METADATA_TAG.len()..];
+        match <u8 as BorshDeserialize>::deserialize(buf)? {
+            2 => Ok(VersionedTokenMetadata::V2(BorshDeserialize::deserialize(
+                buf,
+            )?)),
+            version => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Unknown token metadata version {}", version),
+            )),
+        }
+    }
+}
+
+impl From<VersionedTokenMetadata> for TokenMetadata {
+    fn from(metadata: VersionedTokenMetadata) -> Self {
+        match metadata {
+            VersionedTokenMetadata::V1(metadata) => TokenMetadata {
+                title: metadata.title,
+                media: metadata.media,
+                media_hash: metadata.media_hash,
+                attributes: metadata.attributes,
+                description: None,
+                reference: None,
+                reference_hash: None,
+            },
+            VersionedTokenMetadata::V2(metadata) => metadata,
+        }
+    }
+}
+
+impl From<TokenMetadata> for VersionedTokenMetadata {
+    fn from(metadata: TokenMetadata) -> Self {
+        VersionedTokenMetadata::V2(metadata)
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize)]
@@ -99,6 +184,16 @@ pub struct JsonMetadata {
     pub reference_hash: Option<String>, // Base64-encoded sha256 hash of JSON from reference field. Required if `reference` is included.
 }
 
+/// Fields of a minted token that can evolve, fields that are not set stay unchanged
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenMetadataUpdate {
+    pub title: Option<String>,
+    pub media: Option<String>,
+    pub media_hash: Option<String>,
+    pub attributes: Option<String>,
+}
+
 pub trait NftMetadata {
     //view call for returning the contract metadata
     fn nft_metadata(&self) -> ContractMetadata;
@@ -110,3 +205,147 @@ impl NftMetadata for Contract {
         self.metadata.get().unwrap()
     }
 }
+
+/*********************/
+/* Contract Metadata */
+/*********************/
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn ctrl_set_metadata(&mut self, metadata: ContractMetadata) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner of the contract
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can set metadata",
+        );
+        self.internal_require_no_timelock("Metadata changes must be queued through the timelock");
+        self.internal_set_metadata(metadata);
+    }
+}
+
+impl Contract {
+    //stored metadata of the token converted to the current layout
+    pub(crate) fn internal_token_metadata(&self, token_id: &TokenId) -> Option<TokenMetadata> {
+        self.meta_data_by_id.get(token_id).map(TokenMetadata::from)
+    }
+
+    pub(crate) fn internal_set_metadata(&mut self, metadata: ContractMetadata) {
+        require!(
+            metadata.spec.starts_with("nft-"),
+            "Metadata spec must start with nft-"
+        );
+        require!(
+            !metadata.name.is_empty() && !metadata.symbol.is_empty(),
+            "Metadata name and symbol are required",
+        );
+        if let Some(icon) = metadata.icon.as_ref() {
+            require!(
+                icon.starts_with("data:"),
+                "Metadata icon must be a data URL"
+            );
+        }
+        if metadata.reference.is_some() {
+            require!(
+                metadata.reference_hash.is_some(),
+                "Metadata reference_hash is required when reference is set",
+            );
+        }
+        self.metadata.set(&metadata);
+
+        // Construct the metadata update log as per the events standard.
+        let contract_metadata_update_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard ("nft-1.1.0").
+            version: NFT_STANDARD_SPEC.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::ContractMetadataUpdate(vec![ContractMetadataUpdateLog {
+                // An optional memo to include.
+                memo: None,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&contract_metadata_update_log.to_string());
+    }
+}
+
+/******************/
+/* Token Metadata */
+/******************/
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn nft_update_metadata(&mut self, token_id: TokenId, update: TokenMetadataUpdate) {
+        //storage so we need at least one yocto
+        require_at_least_one_yocto();
+        //require the the sender is the owner, an admin or the evolution contract
+        require!(
+            self.internal_has_role(&env::predecessor_account_id(), Role::Admin)
+                || self.internal_has_role(&env::predecessor_account_id(), Role::Evolver),
+            "Only owner, admin or evolver can update token metadata",
+        );
+        require!(
+            self.tokens_by_id.get(&token_id).is_some(),
+            "Only minted tokens can be updated",
+        );
+        //a new media always comes with its hash
+        require!(
+            update.media.is_some() == update.media_hash.is_some(),
+            "Media and media_hash must be updated together",
+        );
+
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+
+        let mut metadata = self
+            .internal_token_metadata(&token_id)
+            .expect("metadata not found");
+        if let Some(title) = update.title {
+            metadata.title = title;
+        }
+        if let Some(media) = update.media {
+            metadata.media = media;
+        }
+        if let Some(media_hash) = update.media_hash {
+            metadata.media_hash = media_hash;
+        }
+        let attributes_updated = update.attributes.is_some();
+        if let Some(attributes) = update.attributes {
+            metadata.attributes = attributes;
+        }
+        self.meta_data_by_id.insert(&token_id, &metadata.into());
+        //move the token to its new trait values
+        if attributes_updated {
+            self.internal_unindex_traits(&token_id);
+            self.internal_index_traits(&token_id);
+        }
+        self.updated_at_by_id
+            .insert(&token_id, &env::block_timestamp());
+
+        //refund any excess storage if the caller attached too much. Panic when short.
+        refund_deposit(env::storage_usage().saturating_sub(initial_storage_usage));
+
+        // Construct the metadata update log as per the events standard.
+        let nft_metadata_update_log: EventLog = EventLog {
+            // Standard name ("nep171").
+            standard: NFT_STANDARD_NAME.to_string(),
+            // Version of the standard ("nft-1.1.0").
+            version: NFT_STANDARD_SPEC.to_string(),
+            // The data related with the event stored in a vector.
+            event: EventLogVariant::NftMetadataUpdate(vec![NftMetadataUpdateLog {
+                // Vector of token IDs that were updated.
+                token_ids: vec![token_id],
+                // An optional memo to include.
+                memo: None,
+            }]),
+        };
+
+        // Log the serialized json.
+        env::log_str(&nft_metadata_update_log.to_string());
+    }
+}
@@ -9,18 +9,20 @@ use crate::*;
 #[near_bindgen]
 impl Contract {
     #[payable]
-    pub fn nft_register(&mut self, token_list: HashMap<String, TokenMetadata>) {
+    pub fn nft_register(
+        &mut self,
+        token_list: HashMap<String, TokenMetadata>,
+        royalties: Option<HashMap<String, HashMap<AccountId, u32>>>,
+    ) {
         //storage so we need at least one yocto
         require_at_least_one_yocto();
 
-        //require the the sender is the owner of the contract
+        //require the the sender is the owner or a minter
+        self.internal_require_role(Role::Minter, "Only owner or minter can register tokens");
+        self.internal_require_mint_queue_filled();
+        //enforce the token supply cap, burned tokens keep their place in the mint queue
         require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner is allow to register tokens",
-        );
-        //enforce the token supply cap
-        require!(
-            token_list.len() + (self.meta_data_by_id.len() as usize) <= 1000,
+            token_list.len() as u64 + self.mint_queue.len() <= 1000,
             "Max supply of 1000 tokens reached",
         );
 
@@ -28,10 +30,33 @@ impl Contract {
         let initial_storage_usage = env::storage_usage();
 
         for (token_id, metadata) in &token_list {
+            //the reference can only be verified with its hash
             require!(
-                self.meta_data_by_id.insert(token_id, &metadata).is_none(),
+                metadata.reference.is_none() || metadata.reference_hash.is_some(),
+                "Token reference_hash is required when reference is set",
+            );
+            //burned tokens can lose their metadata, their ID still can not be used again
+            require!(
+                self.position_by_id.get(token_id).is_none(),
+                "Token id was already minted",
+            );
+            require!(
+                self.meta_data_by_id
+                    .insert(token_id, &metadata.clone().into())
+                    .is_none(),
                 "Token id is already registered"
             );
+            //new tokens are minted after all tokens that were registered before
+            self.mint_queue.push(token_id);
+        }
+
+        //store the creator royalties of guest artists next to the metadata
+        for (token_id, token_royalties) in royalties.unwrap_or_default() {
+            require!(
+                token_list.contains_key(&token_id),
+                "Royalties can only be set for registered tokens",
+            );
+            self.internal_set_token_royalties(token_id, token_royalties);
         }
 
         //refund any excess storage if the owner attached too much. Panic when short.
@@ -43,7 +68,13 @@ impl Contract {
         //storage so we need at least one yocto
         require_at_least_one_yocto();
 
+        //minting can be halted by a pauser
+        require!(!self.mint_paused, "Minting is paused");
+        self.internal_require_mint_queue_filled();
+
         let sender_id = env::predecessor_account_id();
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
 
         //ensure that the predecessor can mint tokens
         let mint_info = self.mint_info.get().expect("Mint info not found");
@@ -76,19 +107,17 @@ impl Contract {
             ),
         }
 
+        //the head of the queue is the next token, burned tokens are not minted again
         require!(
-            self.tokens_by_id.len() < self.meta_data_by_id.len(),
+            self.mint_queue_head < self.mint_queue.len(),
             "Out of tokens to mint"
         );
-
-        let list: Vec<String> = self
-            .meta_data_by_id
-            .keys()
-            .skip(self.tokens_by_id.len() as usize)
-            .take(1)
-            .collect();
-
-        let token_id = list.get(0).expect("Unable to find token data");
+        let token_id = self
+            .mint_queue
+            .get(self.mint_queue_head)
+            .expect("Unable to find token data");
+        self.position_by_id.insert(&token_id, &self.mint_queue_head);
+        self.mint_queue_head += 1;
 
         //specify the token struct that contains the owner ID
         let token = Token {
@@ -105,6 +134,8 @@ impl Contract {
 
         //call the internal method for adding the token to the owner
         self.internal_add_token_to_owner(&token.owner_id, &token_id);
+        //make the token searchable by its traits
+        self.internal_index_traits(&token_id);
 
         // Construct the mint log as per the events standard.
         let nft_mint_log: EventLog = EventLog {
@@ -135,6 +166,20 @@ impl Contract {
             },
         );
 
+        //accounts with a storage balance pay the storage from it, otherwise the mint price covers it
+        let storage_used = env::storage_usage() - initial_storage_usage;
+        let mut revenue = env::attached_deposit();
+        if self.internal_use_storage_balance(&sender_id, storage_used) {
+            //refund the share of the deposit that would have paid for the storage
+            let refund = revenue.min(Balance::from(storage_used) * env::storage_byte_cost());
+            if refund > 0 {
+                Promise::new(sender_id.clone()).transfer(refund);
+            }
+            revenue -= refund;
+        }
+        //keep track of the payment for the treasury
+        self.internal_add_mint_revenue(revenue);
+
         let tokens = self
             .tokens_per_owner
             .get(&sender_id)
@@ -175,12 +220,38 @@ impl Contract {
     pub fn nft_set_mint_info(&mut self, info: MintInfo) {
         //require that the owner attached 1 yoctoNEAR for security reasons
         require_one_yocto();
-        //require the the sender is the owner of the contract
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can set mint info");
+        //price changes have to wait in the timelock
+        self.internal_require_no_timelock("Mint info changes must be queued through the timelock");
+        self.internal_set_mint_info(info);
+    }
+
+    pub fn nft_mint_paused(&self) -> bool {
+        self.mint_paused
+    }
+
+    #[payable]
+    pub fn nft_set_mint_paused(&mut self, paused: bool) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or a pauser
+        self.internal_require_role(Role::Pauser, "Only owner or pauser can pause minting");
+        self.mint_paused = paused;
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_set_mint_info(&mut self, info: MintInfo) {
+        self.mint_info.set(&info);
+    }
+
+    //the tokens registered before the upgrade are queued first so they keep their mint order
+    pub(crate) fn internal_require_mint_queue_filled(&self) {
         require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can set mint info",
+            self.mint_queue_pending == 0,
+            "The mint queue has to be filled after the upgrade",
         );
-        self.mint_info.set(&info);
     }
 }
 
@@ -194,10 +265,10 @@ impl Contract {
     pub fn nft_allow_minting(&mut self, account_id: AccountId, amount: u32) {
         //require that the owner attached 1 yoctoNEAR for security reasons
         require_one_yocto();
-        //require the the sender is the owner of the contract
-        require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can allow minting access",
+        //require the the sender is the owner or a whitelist manager
+        self.internal_require_role(
+            Role::WhitelistManager,
+            "Only owner or whitelist manager can allow minting access",
         );
         //insert the account and the limit to the minting whitelist
         self.mint_state_list.insert(
@@ -213,10 +284,10 @@ impl Contract {
     pub fn nft_revoke_minting(&mut self, account_id: AccountId) {
         //require that the owner attached 1 yoctoNEAR for security reasons
         require_one_yocto();
-        //require the the sender is the owner of the contract
-        require!(
-            env::predecessor_account_id() == self.owner_id,
-            "Only owner can revoke minting access",
+        //require the the sender is the owner or a whitelist manager
+        self.internal_require_role(
+            Role::WhitelistManager,
+            "Only owner or whitelist manager can revoke minting access",
         );
         //remove the account to the minting whitelist
         self.mint_state_list.remove(&account_id);
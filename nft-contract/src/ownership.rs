@@ -0,0 +1,122 @@
+use near_sdk::require;
+
+use crate::*;
+
+/**********************/
+/* Ownership Transfer */
+/**********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_pending_owner(&self) -> Option<AccountId> {
+        self.pending_owner_id.clone()
+    }
+
+    #[payable]
+    pub fn ctrl_propose_owner(&mut self, new_owner_id: AccountId) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner of the contract
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can propose a new owner",
+        );
+        require!(
+            new_owner_id != self.owner_id,
+            "Account is already the owner"
+        );
+
+        //the proposal only takes effect once the new owner accepts it
+        self.pending_owner_id = Some(new_owner_id.clone());
+
+        log_aura_event(EventLogVariant::OwnerPropose(vec![OwnerLog {
+            old_owner_id: self.owner_id.to_string(),
+            new_owner_id: new_owner_id.to_string(),
+        }]));
+    }
+
+    #[payable]
+    pub fn ctrl_accept_ownership(&mut self) {
+        //require that the new owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let new_owner_id = self
+            .pending_owner_id
+            .clone()
+            .expect("No ownership transfer is pending");
+        //require the the sender is the proposed owner
+        require!(
+            env::predecessor_account_id() == new_owner_id,
+            "Only the proposed owner can accept ownership",
+        );
+
+        let old_owner_id = std::mem::replace(&mut self.owner_id, new_owner_id.clone());
+        self.pending_owner_id = None;
+
+        log_aura_event(EventLogVariant::OwnerTransfer(vec![OwnerLog {
+            old_owner_id: old_owner_id.to_string(),
+            new_owner_id: new_owner_id.to_string(),
+        }]));
+    }
+
+    #[payable]
+    pub fn ctrl_cancel_ownership(&mut self) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner of the contract
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can cancel an ownership transfer",
+        );
+
+        let new_owner_id = self
+            .pending_owner_id
+            .take()
+            .expect("No ownership transfer is pending");
+
+        log_aura_event(EventLogVariant::OwnerCancel(vec![OwnerLog {
+            old_owner_id: self.owner_id.to_string(),
+            new_owner_id: new_owner_id.to_string(),
+        }]));
+    }
+}
+
+/*******************/
+/* Royalty Account */
+/*******************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_royalty_account(&self) -> AccountId {
+        self.internal_royalty_account_id()
+    }
+
+    #[payable]
+    pub fn ctrl_set_royalty_account(&mut self, account_id: Option<AccountId>) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner of the contract
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can set the royalty account",
+        );
+
+        //without a royalty account the royalties follow the current owner
+        self.royalty_account_id = account_id;
+
+        log_aura_event(EventLogVariant::RoyaltyAccountUpdate(vec![
+            RoyaltyAccountLog {
+                royalty_account_id: self.internal_royalty_account_id().to_string(),
+            },
+        ]));
+    }
+}
+
+impl Contract {
+    //account that receives the collection royalties
+    pub(crate) fn internal_royalty_account_id(&self) -> AccountId {
+        self.royalty_account_id
+            .clone()
+            .unwrap_or_else(|| self.owner_id.clone())
+    }
+}
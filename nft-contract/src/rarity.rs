@@ -0,0 +1,279 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Precision of the rarity score, a trait that every token has adds this much
+pub const RARITY_PRECISION: u64 = 1000;
+
+/// Step of the paged rarity computation
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum RarityPhase {
+    //counting how often each trait value appears
+    Count,
+    //scoring the tokens from the trait frequencies
+    Score,
+    //ranking the tokens by their score
+    Rank,
+    //no computation is running
+    Done,
+}
+
+/// Progress of the paged rarity computation
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RarityProgress {
+    pub phase: RarityPhase,
+    //tokens handled in the current phase
+    pub processed: u64,
+    //registered tokens when the computation started
+    pub total: u64,
+    //last token that got its rank, the next rank pass continues after it
+    pub last_ranked: Option<TokenId>,
+}
+
+impl Default for RarityProgress {
+    fn default() -> Self {
+        Self {
+            phase: RarityPhase::Done,
+            processed: 0,
+            total: 0,
+            last_ranked: None,
+        }
+    }
+}
+
+/// Rarity of a token as of the last computation
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Rarity {
+    //sum of the inverse frequencies of the token traits
+    pub score: u64,
+    //1 is the rarest token, 0 until the rank pass reached the token
+    pub rank: u64,
+}
+
+//score of the traits, every trait adds the number of tokens divided by the tokens sharing its value
+pub(crate) fn _rarity_score(
+    traits: &[TokenTrait],
+    frequencies: &HashMap<String, HashMap<String, u64>>,
+    total: u64,
+) -> u64 {
+    traits
+        .iter()
+        .filter_map(|token_trait| {
+            frequencies
+                .get(&token_trait.trait_type)?
+                .get(&token_trait.value)
+                .cloned()
+        })
+        .map(|frequency| total * RARITY_PRECISION / frequency)
+        .sum()
+}
+
+//ranking key, ascending order puts the highest score first and breaks ties by token ID
+fn _ranking_key(score: u64, token_id: TokenId) -> (u64, TokenId) {
+    (u64::MAX - score, token_id)
+}
+
+/***************/
+/* Rarity View */
+/***************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_rarity(&self, token_id: TokenId) -> Option<Rarity> {
+        self.rarity_by_id.get(&token_id)
+    }
+
+    //number of registered tokens for every trait type and value, as of the last count pass
+    pub fn nft_trait_frequencies(&self) -> HashMap<String, HashMap<String, u64>> {
+        self.trait_frequencies.iter().collect()
+    }
+
+    pub fn nft_rarity_progress(&self) -> RarityProgress {
+        self.rarity_progress.clone()
+    }
+}
+
+/*****************/
+/* Rarity Passes */
+/*****************/
+
+#[near_bindgen]
+impl Contract {
+    //starts over with the registered tokens, the stored scores stay until they are replaced
+    #[payable]
+    pub fn ctrl_start_rarity(&mut self) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can compute rarity");
+
+        self.trait_frequencies.clear();
+        self.rarity_progress = RarityProgress {
+            phase: RarityPhase::Count,
+            processed: 0,
+            total: self.meta_data_by_id.len(),
+            last_ranked: None,
+        };
+    }
+
+    /*
+        runs the next part of the computation over at most limit tokens.
+        the count, score and rank passes each go over all registered tokens,
+        call this until the returned phase is Done.
+    */
+    #[payable]
+    pub fn ctrl_compute_rarity(&mut self, limit: Option<u64>) -> RarityProgress {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can compute rarity");
+
+        let mut progress = self.rarity_progress.clone();
+        require!(
+            progress.phase != RarityPhase::Done,
+            "Rarity computation has not been started",
+        );
+        //the passes rely on the order of the registered tokens
+        require!(
+            progress.total == self.meta_data_by_id.len(),
+            "Registered tokens changed, rarity computation has to be started again",
+        );
+
+        let limit = limit.unwrap_or(50);
+        let handled = match progress.phase {
+            RarityPhase::Count => self.internal_count_traits(progress.processed, limit),
+            RarityPhase::Score => self.internal_score_tokens(progress.processed, limit),
+            RarityPhase::Rank => {
+                let (handled, last_ranked) = self.internal_rank_tokens(
+                    progress.processed,
+                    progress.last_ranked.clone(),
+                    limit,
+                );
+                progress.last_ranked = last_ranked;
+                handled
+            }
+            RarityPhase::Done => 0,
+        };
+
+        //move on to the next pass once every token was handled
+        progress.processed += handled;
+        if progress.processed >= progress.total {
+            progress.phase = match progress.phase {
+                RarityPhase::Count => RarityPhase::Score,
+                RarityPhase::Score => RarityPhase::Rank,
+                _ => RarityPhase::Done,
+            };
+            progress.processed = 0;
+        }
+
+        self.rarity_progress = progress.clone();
+        progress
+    }
+}
+
+impl Contract {
+    fn internal_count_traits(&mut self, start: u64, limit: u64) -> u64 {
+        let list: Vec<TokenId> = self
+            .meta_data_by_id
+            .keys()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect();
+
+        let mut frequencies: HashMap<String, HashMap<String, u64>> =
+            self.trait_frequencies.iter().collect();
+        for token_id in list.iter() {
+            let metadata = self
+                .internal_token_metadata(token_id)
+                .expect("metadata not found");
+            for token_trait in _token_traits(&metadata.attributes) {
+                *frequencies
+                    .entry(token_trait.trait_type)
+                    .or_default()
+                    .entry(token_trait.value)
+                    .or_insert(0) += 1;
+            }
+        }
+        for (trait_type, counts) in frequencies.iter() {
+            self.trait_frequencies.insert(trait_type, counts);
+        }
+
+        list.len() as u64
+    }
+
+    fn internal_score_tokens(&mut self, start: u64, limit: u64) -> u64 {
+        let list: Vec<TokenId> = self
+            .meta_data_by_id
+            .keys()
+            .skip(start as usize)
+            .take(limit as usize)
+            .collect();
+
+        let frequencies: HashMap<String, HashMap<String, u64>> =
+            self.trait_frequencies.iter().collect();
+        let total = self.rarity_progress.total;
+        for token_id in list.iter() {
+            let metadata = self
+                .internal_token_metadata(token_id)
+                .expect("metadata not found");
+            let score = _rarity_score(&_token_traits(&metadata.attributes), &frequencies, total);
+
+            //replace the ranking of the previous computation
+            self.internal_remove_rarity(token_id);
+            self.rarity_ranking
+                .insert(&_ranking_key(score, token_id.clone()), &());
+            self.rarity_by_id
+                .insert(token_id, &Rarity { score, rank: 0 });
+        }
+
+        list.len() as u64
+    }
+
+    fn internal_rank_tokens(
+        &mut self,
+        start: u64,
+        last_ranked: Option<TokenId>,
+        limit: u64,
+    ) -> (u64, Option<TokenId>) {
+        //continue after the last ranked token
+        let list: Vec<TokenId> = match last_ranked {
+            Some(token_id) => {
+                let score = self
+                    .rarity_by_id
+                    .get(&token_id)
+                    .expect("Rarity not found")
+                    .score;
+                self.rarity_ranking
+                    .iter_from(_ranking_key(score, token_id))
+                    .take(limit as usize)
+                    .map(|((_, token_id), _)| token_id)
+                    .collect()
+            }
+            None => self
+                .rarity_ranking
+                .iter()
+                .take(limit as usize)
+                .map(|((_, token_id), _)| token_id)
+                .collect(),
+        };
+
+        for (index, token_id) in list.iter().enumerate() {
+            let mut rarity = self.rarity_by_id.get(token_id).expect("Rarity not found");
+            rarity.rank = start + index as u64 + 1;
+            self.rarity_by_id.insert(token_id, &rarity);
+        }
+
+        (list.len() as u64, list.last().cloned())
+    }
+
+    //removes the stored rarity of a token
+    pub(crate) fn internal_remove_rarity(&mut self, token_id: &TokenId) {
+        if let Some(rarity) = self.rarity_by_id.remove(token_id) {
+            self.rarity_ranking
+                .remove(&_ranking_key(rarity.score, token_id.clone()));
+        }
+    }
+}
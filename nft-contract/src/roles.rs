@@ -0,0 +1,162 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Privileged roles that can be granted to accounts besides the contract owner
+#[derive(
+    BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Copy, PartialEq, Debug,
+)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Minter,
+    WhitelistManager,
+    Treasurer,
+    Pauser,
+    //contract or account that evolves the metadata of minted tokens
+    Evolver,
+}
+
+impl Role {
+    //name of the role as it is used in the json interface and event logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Minter => "minter",
+            Role::WhitelistManager => "whitelist_manager",
+            Role::Treasurer => "treasurer",
+            Role::Pauser => "pauser",
+            Role::Evolver => "evolver",
+        }
+    }
+}
+
+/*****************/
+/* Role Control  */
+/*****************/
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn ctrl_grant_role(&mut self, account_id: AccountId, role: Role) {
+        //require that the caller attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //only the owner can hand out admin and treasurer, admins can hand out every other role
+        self.internal_require_role_manager(role);
+        //new privileges have to wait in the timelock
+        self.internal_require_no_timelock("Role grants must be queued through the timelock");
+        self.internal_grant_role(account_id, role);
+    }
+
+    #[payable]
+    pub fn ctrl_revoke_role(&mut self, account_id: AccountId, role: Role) {
+        //require that the caller attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //only the owner can take away admin and treasurer, admins can take away every other role
+        self.internal_require_role_manager(role);
+
+        let mut roles = self.roles.get(&account_id).unwrap_or_default();
+        require!(roles.contains(&role), "Account does not have this role");
+        roles.retain(|r| r != &role);
+
+        //remove the account entirely once it holds no more roles
+        if roles.is_empty() {
+            self.roles.remove(&account_id);
+        } else {
+            self.roles.insert(&account_id, &roles);
+        }
+
+        log_aura_event(EventLogVariant::RoleRevoke(vec![RoleLog {
+            account_id: account_id.to_string(),
+            role: role.as_str().to_string(),
+            sender_id: env::predecessor_account_id().to_string(),
+        }]));
+    }
+}
+
+/********************/
+/* Role Enumeration */
+/********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_has_role(&self, account_id: AccountId, role: Role) -> bool {
+        self.internal_has_role(&account_id, role)
+    }
+
+    pub fn ctrl_roles_for(&self, account_id: AccountId) -> Vec<Role> {
+        self.roles.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn ctrl_roles(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> HashMap<AccountId, Vec<Role>> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.roles
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    //the owner implicitly holds every role, other accounts need an explicit grant
+    pub(crate) fn internal_has_role(&self, account_id: &AccountId, role: Role) -> bool {
+        if account_id == &self.owner_id {
+            return true;
+        }
+        self.roles
+            .get(account_id)
+            .map(|roles| roles.contains(&role))
+            .unwrap_or(false)
+    }
+
+    //require that the predecessor is the owner or holds the given role
+    pub(crate) fn internal_require_role(&self, role: Role, message: &str) {
+        require!(
+            self.internal_has_role(&env::predecessor_account_id(), role),
+            message
+        );
+    }
+
+    pub(crate) fn internal_grant_role(&mut self, account_id: AccountId, role: Role) {
+        let mut roles = self.roles.get(&account_id).unwrap_or_default();
+        require!(!roles.contains(&role), "Account already has this role");
+        roles.push(role);
+        self.roles.insert(&account_id, &roles);
+
+        log_aura_event(EventLogVariant::RoleGrant(vec![RoleLog {
+            account_id: account_id.to_string(),
+            role: role.as_str().to_string(),
+            sender_id: env::predecessor_account_id().to_string(),
+        }]));
+    }
+
+    //require that the predecessor is allowed to grant or revoke the given role
+    pub(crate) fn internal_require_role_manager(&self, role: Role) {
+        let sender_id = env::predecessor_account_id();
+        match role {
+            Role::Admin => require!(
+                sender_id == self.owner_id,
+                "Only owner can manage the admin role"
+            ),
+            //an admin could otherwise grant itself access to the funds
+            Role::Treasurer => require!(
+                sender_id == self.owner_id,
+                "Only owner can manage the treasurer role"
+            ),
+            _ => require!(
+                self.internal_has_role(&sender_id, Role::Admin),
+                "Only owner or admin can manage roles"
+            ),
+        }
+    }
+}
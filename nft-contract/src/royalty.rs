@@ -2,9 +2,12 @@ use near_sdk::require;
 
 use crate::*;
 
-/// Royalty amount in basepoints for the contract owner
+/// Royalty amount in basepoints for the contract owner, used until a royalty table is set
 pub const MANAGER_ROYALTY: u32 = 600;
 
+/// Maximum number of accounts in the collection royalty table
+pub const MAX_ROYALTY_ACCOUNTS: usize = 10;
+
 pub trait NftRoyalties {
     //calculates the payout for a token given the passed in balance. This is a view method
     fn nft_payout(&self, token_id: TokenId, balance: U128, max_len_payout: u32) -> Payout;
@@ -21,19 +24,48 @@ pub trait NftRoyalties {
     ) -> Payout;
 }
 
-fn _compute_payout(owner_id: AccountId, manager_id: AccountId, balance: u128) -> Payout {
+//sum of the royalties in basis points
+pub(crate) fn _total_royalty(royalties: &HashMap<AccountId, u32>) -> u64 {
+    royalties.values().map(|royalty| u64::from(*royalty)).sum()
+}
+
+/*
+    splits the balance between the royalty accounts and the owner following NEP-199.
+    an owner that also receives royalties gets a single entry and the owner receives
+    the rounding remainder, so the payout always adds up to the balance.
+*/
+pub(crate) fn _compute_payout(
+    owner_id: AccountId,
+    royalties: HashMap<AccountId, u32>,
+    balance: u128,
+    max_len_payout: u32,
+) -> Payout {
     //keep track of the payout object to send back
     let mut payout_object = Payout {
         payout: HashMap::new(),
     };
 
-    // payout to previous owner who gets 100% - 6% managment fees
-    payout_object
-        .payout
-        .insert(manager_id, royalty_to_payout(MANAGER_ROYALTY, balance));
-    payout_object.payout.insert(
-        owner_id,
-        royalty_to_payout(10000 - MANAGER_ROYALTY, balance),
+    //pay out every royalty account, rounded down
+    let mut paid: u128 = 0;
+    for (account_id, royalty) in royalties {
+        let amount = royalty_to_payout(royalty, balance).0;
+        paid += amount;
+
+        let entry = payout_object.payout.entry(account_id).or_insert(U128(0));
+        entry.0 += amount;
+    }
+
+    //previous owner gets whatever is left of the balance
+    let entry = payout_object.payout.entry(owner_id).or_insert(U128(0));
+    entry.0 += balance - paid;
+
+    //make sure we're not paying out to too many people (GAS limits)
+    require!(
+        payout_object.payout.len() <= max_len_payout as usize,
+        format!(
+            "Market cannot payout royalties to {} accounts",
+            payout_object.payout.len()
+        ),
     );
 
     //return the payout object
@@ -47,11 +79,13 @@ impl NftRoyalties for Contract {
         //get the token object
         let token = self.tokens_by_id.get(&token_id).expect("Token not found");
 
-        //make sure we're not paying out to too many people (GAS limits)
-        require!(max_len_payout > 0, "Market cannot payout royalties");
-
         // Compute and return the payout list
-        _compute_payout(token.owner_id, self.owner_id.clone(), u128::from(balance))
+        _compute_payout(
+            token.owner_id,
+            self.internal_token_royalties(&token_id),
+            u128::from(balance),
+            max_len_payout,
+        )
     }
 
     //transfers the token to the receiver ID and returns the payout object that should be payed given the passed in balance.
@@ -68,18 +102,169 @@ impl NftRoyalties for Contract {
         //require that the user attached 1 yocto NEAR for security reasons
         require_one_yocto();
 
-        //make sure we're not paying out to too many people (GAS limits)
-        require!(max_len_payout > 0, "Market cannot payout royalties");
-
+        //compute the payout before the transfer so a market that can not pay it fails early
+        let owner_id = self
+            .tokens_by_id
+            .get(&token_id)
+            .expect("Token not found")
+            .owner_id;
         //get the sender ID
         let sender_id = env::predecessor_account_id();
+        //approvals given before the enforcement or to a removed marketplace can not settle sales
+        if sender_id != owner_id {
+            self.internal_require_marketplace(
+                &sender_id,
+                "Only allowlisted marketplaces can sell while royalties are enforced",
+            );
+        }
+        //zero balance sales would skip the royalties
+        let is_sale = self.internal_require_sale_price(&token_id, &receiver_id, balance.0);
+        let payout = _compute_payout(
+            owner_id,
+            self.internal_token_royalties(&token_id),
+            u128::from(balance),
+            max_len_payout,
+        );
+
         //transfer the token to the passed in receiver and get the previous token object back
         let token = self.internal_transfer(&sender_id, &receiver_id, &token_id, approval_id, memo);
 
         //refund the previous token owner for the storage used up by the previous approved account IDs
         refund_approved_account_ids(token.owner_id.clone(), &token.approved_account_ids);
 
-        // Compute and return the payout list
-        _compute_payout(token.owner_id, self.owner_id.clone(), u128::from(balance))
+        //keep track of the sale for the collection stats, gifts below the minimum are not sales
+        if is_sale {
+            self.internal_record_sale(&token_id, token.owner_id, receiver_id, balance.0);
+        }
+
+        //return the payout list
+        payout
+    }
+}
+
+/***********************/
+/* Collection Royalty  */
+/***********************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_royalties(&self) -> HashMap<AccountId, u32> {
+        self.internal_royalties()
+    }
+
+    #[payable]
+    pub fn ctrl_set_royalties(&mut self, royalties: Option<HashMap<AccountId, u32>>) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can set royalties");
+
+        if let Some(royalties) = royalties.as_ref() {
+            require!(
+                royalties.len() <= MAX_ROYALTY_ACCOUNTS,
+                format!("Royalties are limited to {} accounts", MAX_ROYALTY_ACCOUNTS),
+            );
+            require!(
+                royalties.values().all(|royalty| *royalty > 0),
+                "Royalties must be above 0 basis points",
+            );
+        }
+        //leave room for the largest creator royalty of a single token, the default royalty included
+        let total = royalties
+            .as_ref()
+            .map_or(u64::from(MANAGER_ROYALTY), _total_royalty);
+        require!(
+            total + u64::from(self.max_token_royalty) < 10000,
+            "Royalties must total less than 10000 basis points",
+        );
+
+        //without a table the default royalty goes to the royalty account
+        match royalties {
+            Some(royalties) => {
+                self.royalties.set(&royalties);
+            }
+            None => {
+                self.royalties.remove();
+            }
+        }
+
+        log_aura_event(EventLogVariant::RoyaltiesUpdate(vec![RoyaltiesLog {
+            royalties: self
+                .internal_royalties()
+                .into_iter()
+                .map(|(account_id, royalty)| (account_id.to_string(), royalty))
+                .collect(),
+        }]));
+    }
+}
+
+impl Contract {
+    //collection royalties merged with the creator royalties of the token
+    pub(crate) fn internal_token_royalties(&self, token_id: &TokenId) -> HashMap<AccountId, u32> {
+        let mut royalties = self.internal_royalties();
+        if let Some(token_royalties) = self.token_royalties.get(token_id) {
+            for (account_id, royalty) in token_royalties {
+                *royalties.entry(account_id).or_insert(0) += royalty;
+            }
+        }
+        royalties
+    }
+
+    pub(crate) fn internal_set_token_royalties(
+        &mut self,
+        token_id: TokenId,
+        royalties: HashMap<AccountId, u32>,
+    ) {
+        require!(
+            royalties.len() <= MAX_ROYALTY_ACCOUNTS,
+            format!("Royalties are limited to {} accounts", MAX_ROYALTY_ACCOUNTS),
+        );
+        require!(
+            royalties.values().all(|royalty| *royalty > 0),
+            "Royalties must be above 0 basis points",
+        );
+        //the owner always has to keep a share of the sale
+        let total = _total_royalty(&royalties);
+        require!(
+            total + _total_royalty(&self.internal_royalties()) < 10000,
+            "Royalties must total less than 10000 basis points",
+        );
+
+        self.max_token_royalty = self.max_token_royalty.max(total as u32);
+        self.token_royalties.insert(&token_id, &royalties);
+    }
+
+    //collection royalties in basis points for each account
+    pub(crate) fn internal_royalties(&self) -> HashMap<AccountId, u32> {
+        self.royalties.get().unwrap_or_else(|| {
+            let mut royalties = HashMap::new();
+            royalties.insert(self.internal_royalty_account_id(), MANAGER_ROYALTY);
+            royalties
+        })
+    }
+}
+
+/*****************/
+/* Token Royalty */
+/*****************/
+
+#[near_bindgen]
+impl Contract {
+    //tokens that pay creator royalties on top of the collection royalties
+    pub fn nft_custom_royalties(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> HashMap<TokenId, HashMap<AccountId, u32>> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.token_royalties
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
     }
 }
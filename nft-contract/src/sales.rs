@@ -0,0 +1,109 @@
+use crate::*;
+
+/// Number of sales kept in the history of a single token
+pub const MAX_SALE_HISTORY: usize = 10;
+
+/// A secondary sale reported by a marketplace through nft_transfer_payout
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleRecord {
+    pub price: U128,
+    pub buyer_id: AccountId,
+    pub seller_id: AccountId,
+    //marketplace contract that settled the sale
+    pub market_id: AccountId,
+    pub timestamp: u64,
+}
+
+/// Running totals of the secondary sales of the collection
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct SaleStats {
+    pub volume: U128,
+    pub count: u64,
+    pub last_price: U128,
+}
+
+impl Default for SaleStats {
+    fn default() -> Self {
+        Self {
+            volume: U128(0),
+            count: 0,
+            last_price: U128(0),
+        }
+    }
+}
+
+/****************/
+/* Sale History */
+/****************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_sale_stats(&self) -> SaleStats {
+        self.sale_stats.clone()
+    }
+
+    //sales of the token, the most recent sale last
+    pub fn nft_sale_history(&self, token_id: TokenId) -> Vec<SaleRecord> {
+        self.sales_by_id.get(&token_id).unwrap_or_default()
+    }
+
+    //most recent sale of every sold token, used to derive the floor price
+    pub fn nft_last_sales(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> HashMap<TokenId, SaleRecord> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.sales_by_id
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .filter_map(|(token_id, sales)| sales.last().cloned().map(|sale| (token_id, sale)))
+            .collect()
+    }
+}
+
+impl Contract {
+    //record a sale in the token history and the collection stats
+    pub(crate) fn internal_record_sale(
+        &mut self,
+        token_id: &TokenId,
+        seller_id: AccountId,
+        buyer_id: AccountId,
+        price: Balance,
+    ) {
+        let sale = SaleRecord {
+            price: U128(price),
+            buyer_id,
+            seller_id,
+            market_id: env::predecessor_account_id(),
+            timestamp: env::block_timestamp(),
+        };
+
+        //keep the history bounded, the oldest sale is dropped first
+        let mut sales = self.sales_by_id.get(token_id).unwrap_or_default();
+        if sales.len() >= MAX_SALE_HISTORY {
+            sales.remove(0);
+        }
+        sales.push(sale.clone());
+        self.sales_by_id.insert(token_id, &sales);
+
+        self.sale_stats.volume = U128(self.sale_stats.volume.0 + price);
+        self.sale_stats.count += 1;
+        self.sale_stats.last_price = U128(price);
+
+        log_aura_event(EventLogVariant::NftSale(vec![NftSaleLog {
+            token_id: token_id.to_string(),
+            seller_id: sale.seller_id.to_string(),
+            buyer_id: sale.buyer_id.to_string(),
+            market_id: sale.market_id.to_string(),
+            price: price.to_string(),
+        }]));
+    }
+}
@@ -0,0 +1,166 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Holder balances frozen at a block, later balance changes keep the old value
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Snapshot {
+    pub snapshot_id: u64,
+    pub block_height: u64,
+    pub timestamp: u64,
+    //number of holders and minted tokens when the snapshot was taken
+    pub holder_count: u64,
+    pub total_supply: u64,
+}
+
+/***********/
+/* Holders */
+/***********/
+
+#[near_bindgen]
+impl Contract {
+    //accounts holding tokens and the number of tokens they hold
+    pub fn nft_holders(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, u64)> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.tokens_per_owner
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .map(|(account_id, tokens)| (account_id, tokens.len()))
+            .collect()
+    }
+
+    pub fn nft_holder_count(&self) -> u64 {
+        self.tokens_per_owner.len()
+    }
+}
+
+/*************/
+/* Snapshots */
+/*************/
+
+#[near_bindgen]
+impl Contract {
+    /*
+        freezes the token balances of all holders and returns the snapshot ID.
+        nothing is copied here, a balance is saved the first time it changes after the snapshot.
+    */
+    #[payable]
+    pub fn ctrl_take_snapshot(&mut self) -> u64 {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can take snapshots");
+
+        let snapshot = Snapshot {
+            snapshot_id: self.snapshots.len() + 1,
+            block_height: env::block_height(),
+            timestamp: env::block_timestamp(),
+            holder_count: self.tokens_per_owner.len(),
+            total_supply: self.tokens_by_id.len(),
+        };
+        self.snapshots.push(&snapshot);
+
+        log_aura_event(EventLogVariant::SnapshotTake(vec![SnapshotLog {
+            snapshot_id: snapshot.snapshot_id,
+            block_height: snapshot.block_height,
+        }]));
+
+        snapshot.snapshot_id
+    }
+
+    pub fn nft_snapshot(&self, snapshot_id: u64) -> Option<Snapshot> {
+        if snapshot_id == 0 {
+            return None;
+        }
+        self.snapshots.get(snapshot_id - 1)
+    }
+
+    //get the number of tokens an account held when the snapshot was taken
+    pub fn nft_supply_for_owner_at(&self, snapshot_id: u64, account_id: AccountId) -> U128 {
+        self.internal_require_snapshot(snapshot_id);
+        U128(self.internal_balance_at(snapshot_id, &account_id) as u128)
+    }
+
+    /*
+        accounts that held tokens when the snapshot was taken, with their balance at the time.
+        the index runs over the current holders followed by the accounts that sold out since,
+        so a page can hold fewer entries than the limit.
+    */
+    pub fn nft_holders_at(
+        &self,
+        snapshot_id: u64,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<(AccountId, u64)> {
+        self.internal_require_snapshot(snapshot_id);
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.tokens_per_owner
+            .keys()
+            .chain(self.past_holders.iter())
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .map(|account_id| {
+                let balance = self.internal_balance_at(snapshot_id, &account_id);
+                (account_id, balance)
+            })
+            .filter(|(_, balance)| *balance > 0)
+            .collect()
+    }
+}
+
+impl Contract {
+    fn internal_require_snapshot(&self, snapshot_id: u64) {
+        require!(
+            snapshot_id > 0 && snapshot_id <= self.snapshots.len(),
+            "Snapshot not found",
+        );
+    }
+
+    //number of tokens of the account when the snapshot was taken
+    fn internal_balance_at(&self, snapshot_id: u64, account_id: &AccountId) -> u64 {
+        //the first checkpoint saved after the snapshot holds the balance of the snapshot
+        let checkpoints = self.balance_checkpoints.get(account_id).unwrap_or_default();
+        let index = checkpoints.partition_point(|(id, _)| *id < snapshot_id);
+        match checkpoints.get(index) {
+            Some((_, balance)) => *balance,
+            //the balance did not change since the snapshot
+            None => self
+                .tokens_per_owner
+                .get(account_id)
+                .map_or(0, |tokens| tokens.len()),
+        }
+    }
+
+    //saves the balance of the account before its first change after the latest snapshot
+    pub(crate) fn internal_checkpoint_balance(&mut self, account_id: &AccountId) {
+        let snapshot_id = self.snapshots.len();
+        if snapshot_id == 0 {
+            return;
+        }
+
+        let mut checkpoints = self.balance_checkpoints.get(account_id).unwrap_or_default();
+        if !matches!(checkpoints.last(), Some((id, _)) if *id >= snapshot_id) {
+            let balance = self
+                .tokens_per_owner
+                .get(account_id)
+                .map_or(0, |tokens| tokens.len());
+            checkpoints.push((snapshot_id, balance));
+            self.balance_checkpoints.insert(account_id, &checkpoints);
+        }
+    }
+}
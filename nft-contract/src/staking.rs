@@ -0,0 +1,252 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Points earned by staked tokens
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StakingConfig {
+    //points per second for every staked token
+    pub token_rate: u32,
+    //extra points per second by trait type and value, e.g. {"Element": {"Fire": 2}}
+    pub trait_rates: HashMap<String, HashMap<String, u32>>,
+}
+
+/// A token locked in the contract by its owner
+#[derive(BorshDeserialize, BorshSerialize, Clone)]
+pub struct Stake {
+    pub owner_id: AccountId,
+    pub staked_at: u64,
+    //block timestamp up to which the points have been claimed
+    pub claimed_at: u64,
+}
+
+//The Json stake is what will be returned from view calls.
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct JsonStake {
+    pub token_id: TokenId,
+    pub owner_id: AccountId,
+    pub staked_at: u64,
+    //points per second earned by the token
+    pub rate: u32,
+    //points earned since the last claim
+    pub unclaimed: U128,
+}
+
+/******************/
+/* Staking Config */
+/******************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn nft_staking_config(&self) -> StakingConfig {
+        self.staking.get().expect("Staking config not found")
+    }
+
+    //rate changes apply to all points that have not been claimed yet
+    #[payable]
+    pub fn ctrl_set_staking_config(&mut self, config: StakingConfig) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(
+            Role::Admin,
+            "Only owner or admin can set the staking config",
+        );
+        self.staking.set(&config);
+    }
+}
+
+/*****************/
+/* Token Staking */
+/*****************/
+
+#[near_bindgen]
+impl Contract {
+    //the owner pays for the stakes from the storage balance or the attached deposit
+    #[payable]
+    pub fn nft_stake(&mut self, token_ids: Vec<TokenId>) {
+        //storage so we need at least one yocto
+        require_at_least_one_yocto();
+
+        let owner_id = env::predecessor_account_id();
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+        for token_id in token_ids.iter() {
+            let token = self.tokens_by_id.get(token_id).expect("Token not found");
+            require!(token.owner_id == owner_id, "Only owner can stake the token");
+            require!(
+                self.stakes_by_id
+                    .insert(
+                        token_id,
+                        &Stake {
+                            owner_id: owner_id.clone(),
+                            staked_at: env::block_timestamp(),
+                            claimed_at: env::block_timestamp(),
+                        },
+                    )
+                    .is_none(),
+                "Token is already staked",
+            );
+        }
+
+        //pay from the storage balance when possible, otherwise from the attached deposit
+        self.internal_pay_storage(&owner_id, env::storage_usage() - initial_storage_usage);
+
+        log_aura_event(EventLogVariant::NftStake(vec![StakeLog {
+            owner_id: owner_id.to_string(),
+            token_ids,
+        }]));
+    }
+
+    #[payable]
+    pub fn nft_unstake(&mut self, token_ids: Vec<TokenId>) {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        //collect the points before the tokens are unlocked
+        let owner_id = env::predecessor_account_id();
+        self.internal_claim_points(&owner_id, &token_ids);
+        //measure the initial storage being used on the contract
+        let initial_storage_usage = env::storage_usage();
+        for token_id in token_ids.iter() {
+            self.stakes_by_id.remove(token_id);
+        }
+        //the owner paid for the stakes, the released storage goes back to it
+        self.internal_release_storage(
+            &owner_id,
+            initial_storage_usage.saturating_sub(env::storage_usage()),
+        );
+
+        log_aura_event(EventLogVariant::NftUnstake(vec![StakeLog {
+            owner_id: owner_id.to_string(),
+            token_ids,
+        }]));
+    }
+
+    #[payable]
+    pub fn nft_claim_points(&mut self, token_ids: Vec<TokenId>) -> U128 {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let owner_id = env::predecessor_account_id();
+        self.internal_claim_points(&owner_id, &token_ids);
+        self.nft_points(owner_id)
+    }
+
+    pub fn nft_points(&self, account_id: AccountId) -> U128 {
+        U128(self.points_by_owner.get(&account_id).unwrap_or(0))
+    }
+
+    pub fn nft_stake_status(&self, token_id: TokenId) -> Option<JsonStake> {
+        self.stakes_by_id.get(&token_id).map(|stake| {
+            let rate = self.internal_staking_rate(&token_id);
+            JsonStake {
+                token_id,
+                owner_id: stake.owner_id,
+                staked_at: stake.staked_at,
+                rate,
+                unclaimed: U128(_staking_points(rate, stake.claimed_at)),
+            }
+        })
+    }
+
+    pub fn nft_stakes_for_owner(
+        &self,
+        account_id: AccountId,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonStake> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.tokens_per_owner
+            .get(&account_id)
+            .map(|tokens| tokens.to_vec())
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|token_id| self.nft_stake_status(token_id))
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+//points earned at the rate since the timestamp
+fn _staking_points(rate: u32, since: u64) -> u128 {
+    let seconds = env::block_timestamp().saturating_sub(since) / 1_000_000_000;
+    u128::from(rate) * u128::from(seconds)
+}
+
+impl Contract {
+    //staked tokens can not be moved or approved until they are unstaked
+    pub(crate) fn internal_require_not_staked(&self, token_id: &TokenId) {
+        require!(
+            self.stakes_by_id.get(token_id).is_none(),
+            "Token is staked and locked",
+        );
+    }
+
+    //points per second of the token, the trait rates are read from the metadata attributes
+    fn internal_staking_rate(&self, token_id: &TokenId) -> u32 {
+        let config = self.nft_staking_config();
+        let metadata = self
+            .internal_token_metadata(token_id)
+            .expect("metadata not found");
+
+        //the rates are set by the owner, a large rate caps the sum instead of failing
+        _token_traits(&metadata.attributes)
+            .iter()
+            .filter_map(|token_trait| {
+                config
+                    .trait_rates
+                    .get(&token_trait.trait_type)?
+                    .get(&token_trait.value)
+                    .cloned()
+            })
+            .fold(config.token_rate, |rate, trait_rate| {
+                rate.saturating_add(trait_rate)
+            })
+    }
+
+    //drops the stake without earning points, used when the token goes back to its previous owner
+    pub(crate) fn internal_clear_stake(&mut self, token_id: &TokenId) {
+        let initial_storage_usage = env::storage_usage();
+        if let Some(stake) = self.stakes_by_id.remove(token_id) {
+            self.internal_release_storage(
+                &stake.owner_id,
+                initial_storage_usage.saturating_sub(env::storage_usage()),
+            );
+        }
+    }
+
+    fn internal_claim_points(&mut self, owner_id: &AccountId, token_ids: &[TokenId]) {
+        let mut points: u128 = 0;
+        for token_id in token_ids {
+            let mut stake = self
+                .stakes_by_id
+                .get(token_id)
+                .expect("Token is not staked");
+            require!(
+                &stake.owner_id == owner_id,
+                "Only owner can claim the staking points",
+            );
+            points += _staking_points(self.internal_staking_rate(token_id), stake.claimed_at);
+            stake.claimed_at = env::block_timestamp();
+            self.stakes_by_id.insert(token_id, &stake);
+        }
+
+        if points > 0 {
+            let total = self.points_by_owner.get(owner_id).unwrap_or(0) + points;
+            self.points_by_owner.insert(owner_id, &total);
+
+            log_aura_event(EventLogVariant::PointsClaim(vec![PointsLog {
+                owner_id: owner_id.to_string(),
+                points: points.to_string(),
+            }]));
+        }
+    }
+}
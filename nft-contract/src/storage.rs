@@ -0,0 +1,215 @@
+use near_sdk::require;
+
+use crate::*;
+
+/*
+    storage used by the balance entry of a registered account, the account ID is
+    counted at its maximum length of 64 bytes plus the 16 byte balance and the key prefix.
+*/
+const STORAGE_ACCOUNT_BYTES: u64 = 64 + 16 + 40;
+
+/// NEP-145 storage balance of an account
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalance {
+    pub total: U128,
+    pub available: U128,
+}
+
+/// NEP-145 storage balance bounds of the contract
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageBalanceBounds {
+    pub min: U128,
+    pub max: Option<U128>,
+}
+
+pub trait StorageManagement {
+    //deposits NEAR for the storage of the account, the predecessor is used when no account is given
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance;
+
+    //withdraws the available storage balance of the predecessor
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance;
+
+    //removes the predecessor and refunds the full storage balance
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool;
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds;
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance>;
+}
+
+/**********************/
+/* Storage Management */
+/**********************/
+
+#[near_bindgen]
+impl StorageManagement for Contract {
+    #[payable]
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let min = self.storage_balance_bounds().min.0;
+
+        let total = match self.storage_deposits.get(&account_id) {
+            //registered accounts only get the deposit back when registering
+            Some(total) if registration_only.unwrap_or(false) => {
+                if amount > 0 {
+                    Promise::new(env::predecessor_account_id()).transfer(amount);
+                }
+                total
+            }
+            Some(total) => total + amount,
+            None => {
+                require!(
+                    amount >= min,
+                    format!("Must attach {} yoctoNEAR to register", min),
+                );
+                //refund everything above the registration when only registering
+                if registration_only.unwrap_or(false) && amount > min {
+                    Promise::new(env::predecessor_account_id()).transfer(amount - min);
+                    min
+                } else {
+                    amount
+                }
+            }
+        };
+
+        self.internal_set_storage_balance(&account_id, Some(total));
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    #[payable]
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let account_id = env::predecessor_account_id();
+        let balance = self
+            .storage_balance_of(account_id.clone())
+            .expect("Account is not registered");
+
+        //withdraw everything that is available when no amount is given
+        let amount = amount.unwrap_or(balance.available).0;
+        require!(
+            amount <= balance.available.0,
+            "Amount exceeds the available storage balance",
+        );
+
+        self.internal_set_storage_balance(&account_id, Some(balance.total.0 - amount));
+        if amount > 0 {
+            Promise::new(account_id.clone()).transfer(amount);
+        }
+        self.storage_balance_of(account_id).unwrap()
+    }
+
+    #[payable]
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        //require that the user attached exactly 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        //tokens are never burned to close an account, NEP-145 lets the contract refuse forcing
+        require!(
+            !force.unwrap_or(false),
+            "Force unregister is not supported, transfer the tokens first",
+        );
+        let account_id = env::predecessor_account_id();
+        match self.storage_deposits.get(&account_id) {
+            Some(total) => {
+                require!(
+                    self.tokens_per_owner.get(&account_id).is_none(),
+                    "Can not unregister an account that owns tokens",
+                );
+                self.internal_set_storage_balance(&account_id, None);
+                Promise::new(account_id).transfer(total);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: U128(Balance::from(STORAGE_ACCOUNT_BYTES) * env::storage_byte_cost()),
+            max: None,
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        let min = self.storage_balance_bounds().min.0;
+        self.storage_deposits
+            .get(&account_id)
+            .map(|total| StorageBalance {
+                total: U128(total),
+                available: U128(total.saturating_sub(min)),
+            })
+    }
+}
+
+impl Contract {
+    /*
+        pays the storage from the prepaid balance of the account.
+        returns false when the account has no balance or it is too low, the caller then
+        falls back to the attached deposit.
+    */
+    pub(crate) fn internal_use_storage_balance(
+        &mut self,
+        account_id: &AccountId,
+        storage_used: u64,
+    ) -> bool {
+        let cost = Balance::from(storage_used) * env::storage_byte_cost();
+        match self.storage_balance_of(account_id.clone()) {
+            Some(balance) if balance.available.0 >= cost => {
+                self.internal_set_storage_balance(account_id, Some(balance.total.0 - cost));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    //pays the storage from the balance of the account when possible, otherwise from the attached deposit
+    pub(crate) fn internal_pay_storage(&mut self, account_id: &AccountId, storage_used: u64) {
+        if self.internal_use_storage_balance(account_id, storage_used) {
+            refund_deposit(0);
+        } else {
+            //refund any excess storage attached by the user. If the user didn't attach enough, panic.
+            refund_deposit(storage_used);
+        }
+    }
+
+    //gives released storage back to the balance of the account, accounts without a balance get a transfer
+    pub(crate) fn internal_release_storage(
+        &mut self,
+        account_id: &AccountId,
+        storage_released: u64,
+    ) {
+        let amount = Balance::from(storage_released) * env::storage_byte_cost();
+        if amount == 0 {
+            return;
+        }
+        match self.storage_deposits.get(account_id) {
+            Some(total) => self.internal_set_storage_balance(account_id, Some(total + amount)),
+            None => {
+                Promise::new(account_id.clone()).transfer(amount);
+            }
+        }
+    }
+
+    //updates the balance of the account and the total held for all accounts
+    fn internal_set_storage_balance(&mut self, account_id: &AccountId, total: Option<Balance>) {
+        let previous = match total {
+            Some(total) => self.storage_deposits.insert(account_id, &total),
+            None => self.storage_deposits.remove(account_id),
+        };
+        self.treasury.storage_deposits =
+            U128(self.treasury.storage_deposits.0 - previous.unwrap_or(0) + total.unwrap_or(0));
+    }
+}
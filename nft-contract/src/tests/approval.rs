@@ -16,6 +16,9 @@ fn _mint_token(
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     testing_env!(VMContextBuilder::new()
@@ -23,7 +26,7 @@ fn _mint_token(
         .attached_deposit(REG_COST)
         .is_view(false)
         .build());
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(creator_id.clone())
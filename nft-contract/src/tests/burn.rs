@@ -0,0 +1,207 @@
+use super::*;
+
+use crate::approval::NftApproval;
+use crate::enumeration::{NftEnumeration, NftMintEnumeration};
+use crate::nft_core::NonFungibleTokenCore;
+
+#[test]
+fn test_nft_burn_keep() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _register_tokens(&mut contract, vec![tkn_a.clone(), tkn_b.clone()]);
+    _mint_next_token(&mut contract, acc_a.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let token_id = contract.nft_tokens_for_owner(acc_a.clone(), None, None)[0]
+        .token_id
+        .clone();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(token_id.clone());
+
+    // The token is gone but its metadata stays registered
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_token(token_id.clone()).is_none());
+    assert!(contract.nft_total_supply() == U128::from(0));
+    assert!(contract.nft_supply_for_owner(acc_a.clone()) == U128::from(0));
+    assert!(contract.nft_registered_supply() == U128::from(2));
+
+    // Minting continues with the next unminted token
+    _mint_next_token(&mut contract, acc_a.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let tokens = contract.nft_tokens_for_owner(acc_a.clone(), None, None);
+    assert!(tokens.len() == 1);
+    assert!(tokens[0].token_id != token_id);
+}
+
+#[test]
+fn test_nft_burn_remove() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_burn_policy(BurnPolicy::Remove);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    // Approved accounts can burn for the owner
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(tkn_a.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_burn_policy() == BurnPolicy::Remove);
+    assert!(contract.nft_token(tkn_a.clone()).is_none());
+    assert!(contract.nft_registered_supply() == U128::from(0));
+}
+
+#[test]
+#[should_panic(expected = "Only owner or approved account can burn the token")]
+fn test_nft_burn_panic_access() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(tkn_a.clone());
+}
+
+#[test]
+fn test_nft_burn_remove_while_minting() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _register_tokens(&mut contract, vec![tkn_b.clone()]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_burn_policy(BurnPolicy::Remove);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(tkn_a.clone());
+
+    // Removing the metadata does not change which token is minted next
+    _mint_next_token(&mut contract, acc_a.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_registered_supply() == U128::from(1));
+    assert!(contract.nft_total_supply() == U128::from(1));
+    let token = contract.nft_token(tkn_b.clone()).expect("must be set");
+    assert!(token.owner_id == acc_a);
+}
+
+#[test]
+#[should_panic(expected = "Token id was already minted")]
+fn test_nft_register_panic_burned() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_burn_policy(BurnPolicy::Remove);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(tkn_a.clone());
+
+    // The removed metadata does not free the token ID
+    _register_tokens(&mut contract, vec![tkn_a.clone()]);
+}
+
+#[test]
+#[should_panic(expected = "Max supply of 1000 tokens reached")]
+fn test_nft_register_panic_supply_after_burn() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    for batch in 0..10 {
+        let size = if batch == 9 { 99 } else { 100 };
+        _register_tokens(
+            &mut contract,
+            (0..size)
+                .map(|i| format!("token.{}.{}", batch, i))
+                .collect(),
+        );
+    }
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_burn_policy(BurnPolicy::Remove);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn(tkn_a.clone());
+
+    // The burned token still counts against the lifetime supply
+    _register_tokens(&mut contract, vec![String::from("token.b")]);
+}
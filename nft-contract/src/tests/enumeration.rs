@@ -2,6 +2,7 @@ use super::*;
 
 use crate::enumeration::NftEnumeration;
 use crate::enumeration::NftMintEnumeration;
+use crate::nft_core::NonFungibleTokenCore;
 
 fn _mint_token(contract: &mut Contract, owner_id: AccountId) {
     testing_env!(VMContextBuilder::new()
@@ -35,9 +36,12 @@ fn _register_token(contract: &mut Contract, token_id: String, creator_id: Accoun
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 }
 
 #[test]
@@ -149,3 +153,79 @@ fn test_nft_enumeration() {
     assert!(map_b.get(&tkn_d).is_some());
     assert!(map_b.get(&tkn_e).is_some());
 }
+
+#[test]
+fn test_nft_tokens_by_cursor() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+    let tkn_d = String::from("token.d");
+    let tkn_e = String::from("token.e");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    for token_id in [&tkn_a, &tkn_b, &tkn_c, &tkn_d, &tkn_e] {
+        _register_token(&mut contract, token_id.clone(), acc_x.clone());
+    }
+    _mint_approve(&mut contract, acc_x.clone(), acc_a.clone());
+    _mint_approve(&mut contract, acc_x.clone(), acc_b.clone());
+    for _ in 0..4 {
+        _mint_token(&mut contract, acc_a.clone());
+    }
+    _mint_token(&mut contract, acc_b.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_a.clone(), None, Some(2));
+    let token_ids: Vec<TokenId> = page.tokens.iter().map(|t| t.token_id.clone()).collect();
+    assert!(token_ids == vec![tkn_a.clone(), tkn_b.clone()]);
+    assert!(page.next_cursor == Some(tkn_b.clone()));
+
+    // Moving a token out of the set does not shift the next page
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_c.clone(), tkn_a.clone(), None, None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_a.clone(), page.next_cursor, Some(2));
+    let token_ids: Vec<TokenId> = page.tokens.iter().map(|t| t.token_id.clone()).collect();
+    assert!(token_ids == vec![tkn_c.clone(), tkn_d.clone()]);
+    assert!(page.next_cursor.is_none());
+
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_c.clone(), None, None);
+    assert!(page.tokens.len() == 1);
+    assert!(page.tokens[0].token_id == tkn_a);
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_x.clone(), None, None);
+    assert!(page.tokens.is_empty());
+    assert!(page.next_cursor.is_none());
+
+    // All tokens in the order they were minted
+    let mut token_ids: Vec<TokenId> = vec![];
+    let mut cursor = None;
+    loop {
+        let page = contract.nft_tokens_by_cursor(cursor, Some(2));
+        token_ids.extend(page.tokens.iter().map(|t| t.token_id.clone()));
+        cursor = page.next_cursor;
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert!(token_ids == vec![tkn_a, tkn_b, tkn_c, tkn_d, tkn_e]);
+}
+
+#[test]
+#[should_panic(expected = "Cursor token not found")]
+fn test_nft_tokens_by_cursor_panic_cursor() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    contract.nft_tokens_by_cursor(Some(String::from("token.a")), None);
+}
@@ -53,3 +53,19 @@ fn nep_format_transfer_all_fields() {
     };
     assert_eq!(expected, log.to_string());
 }
+
+#[test]
+fn nep_format_burn() {
+    let expected = r#"EVENT_JSON:{"standard":"nep171","version":"1.0.0","event":"nft_burn","data":[{"authorized_id":"market.near","owner_id":"user1.near","token_ids":["token"]}]}"#;
+    let log = EventLog {
+        standard: "nep171".to_string(),
+        version: "1.0.0".to_string(),
+        event: EventLogVariant::NftBurn(vec![NftBurnLog {
+            authorized_id: Some("market.near".to_string()),
+            owner_id: "user1.near".to_string(),
+            token_ids: vec!["token".to_string()],
+            memo: None,
+        }]),
+    };
+    assert_eq!(expected, log.to_string());
+}
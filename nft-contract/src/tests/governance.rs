@@ -0,0 +1,628 @@
+use super::*;
+
+use crate::nft_core::NonFungibleTokenCore;
+
+const DEADLINE: u64 = 1_000_000_000_000;
+
+fn _create_proposal(contract: &mut Contract, proposer_id: AccountId) -> u64 {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(proposer_id)
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_create_proposal(
+        "Fund the artist residency".to_string(),
+        vec!["yes".to_string(), "no".to_string()],
+        DEADLINE,
+    )
+}
+
+fn _vote(
+    contract: &mut Contract,
+    voter_id: AccountId,
+    proposal_id: u64,
+    option: u32,
+    token_ids: Vec<String>,
+) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(voter_id)
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_vote(proposal_id, option, token_ids);
+}
+
+#[test]
+fn test_nft_create_proposal() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    // The owner does not need tokens, holders need the threshold
+    assert!(_create_proposal(&mut contract, acc_x.clone()) == 1);
+    assert!(_create_proposal(&mut contract, acc_a.clone()) == 2);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let proposal = contract.nft_proposal(2).expect("must be set");
+    assert!(proposal.proposer_id == acc_a);
+    assert!(proposal.options.len() == 2);
+    assert!(proposal.tallies == vec![0, 0]);
+    assert!(proposal.deadline == DEADLINE);
+    assert!(contract.nft_proposal(0).is_none());
+    assert!(contract.nft_proposal(3).is_none());
+    assert!(contract.nft_proposals(None, None).len() == 2);
+    assert!(contract.nft_proposals(Some(U128(1)), Some(5)).len() == 1);
+}
+
+#[test]
+#[should_panic(expected = "Not enough tokens to create a proposal")]
+fn test_nft_create_proposal_panic_threshold() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_proposal_threshold(2);
+
+    _create_proposal(&mut contract, acc_a.clone());
+}
+
+#[test]
+#[should_panic(expected = "A proposal needs between 2 and 16 options")]
+fn test_nft_create_proposal_panic_options() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_create_proposal("Question".to_string(), vec!["yes".to_string()], DEADLINE);
+}
+
+#[test]
+fn test_nft_vote() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_c.clone(), acc_b.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+    _vote(
+        &mut contract,
+        acc_a.clone(),
+        proposal_id,
+        1,
+        vec![tkn_a.clone(), tkn_b.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let result = contract.nft_proposal_result(proposal_id);
+    assert!(!result.closed);
+    assert!(result.total_votes == 2);
+    assert!(result.winning_option == Some(1));
+    assert!(contract.nft_token_vote(proposal_id, tkn_a.clone()) == Some(1));
+    assert!(contract
+        .nft_token_vote(proposal_id, tkn_c.clone())
+        .is_none());
+
+    // A transferred token keeps its vote, the new owner can not vote with it again
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_b.clone(), None, None);
+    _vote(
+        &mut contract,
+        acc_b.clone(),
+        proposal_id,
+        0,
+        vec![tkn_c.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new()
+        .block_timestamp(DEADLINE + 1)
+        .is_view(true)
+        .build());
+    let proposal = contract.nft_proposal(proposal_id).expect("must be set");
+    assert!(proposal.tallies == vec![1, 2]);
+    let result = contract.nft_proposal_result(proposal_id);
+    assert!(result.closed);
+    assert!(result.total_votes == 3);
+    assert!(result.winning_option == Some(1));
+}
+
+#[test]
+fn test_nft_proposal_result_tie() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_b.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract
+        .nft_proposal_result(proposal_id)
+        .winning_option
+        .is_none());
+
+    _vote(
+        &mut contract,
+        acc_a.clone(),
+        proposal_id,
+        0,
+        vec![tkn_a.clone()],
+    );
+    _vote(
+        &mut contract,
+        acc_b.clone(),
+        proposal_id,
+        1,
+        vec![tkn_b.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let result = contract.nft_proposal_result(proposal_id);
+    assert!(result.total_votes == 2);
+    assert!(result.winning_option.is_none());
+}
+
+#[test]
+#[should_panic(expected = "Token already voted on this proposal")]
+fn test_nft_vote_panic_twice() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+    _vote(
+        &mut contract,
+        acc_a.clone(),
+        proposal_id,
+        0,
+        vec![tkn_a.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_a.clone(), None, None);
+    _vote(
+        &mut contract,
+        acc_b.clone(),
+        proposal_id,
+        1,
+        vec![tkn_a.clone()],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only owner or delegate can vote with the token")]
+fn test_nft_vote_panic_owner() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+    _vote(
+        &mut contract,
+        acc_b.clone(),
+        proposal_id,
+        0,
+        vec![tkn_a.clone()],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Proposal is closed")]
+fn test_nft_vote_panic_closed() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .block_timestamp(DEADLINE + 1)
+        .is_view(false)
+        .build());
+    contract.nft_vote(proposal_id, 0, vec![tkn_a.clone()]);
+}
+
+#[test]
+fn test_nft_vote_storage_balance() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    // Proposals and votes are paid from the storage balance
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let initial_storage_usage = env::storage_usage();
+    let proposal_id = contract.nft_create_proposal(
+        "Fund the artist residency".to_string(),
+        vec!["yes".to_string(), "no".to_string()],
+        DEADLINE,
+    );
+    contract.nft_vote(proposal_id, 0, vec![tkn_a.clone()]);
+    let cost =
+        Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let balance = contract.storage_balance_of(acc_a.clone()).unwrap();
+    assert!(cost > 0);
+    assert!(balance.total.0 == ONE_NEAR - cost);
+}
+
+#[test]
+#[should_panic(expected = "to cover storage")]
+fn test_nft_vote_panic_storage() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_vote(proposal_id, 0, vec![tkn_a.clone()]);
+}
+
+fn _delegate(
+    contract: &mut Contract,
+    owner_id: AccountId,
+    delegate_id: AccountId,
+    token_ids: Vec<String>,
+) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(owner_id)
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_delegate(delegate_id, token_ids);
+}
+
+#[test]
+fn test_nft_delegate() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_c.clone(), acc_b.clone());
+
+    _delegate(
+        &mut contract,
+        acc_a.clone(),
+        acc_c.clone(),
+        vec![tkn_a.clone(), tkn_b.clone()],
+    );
+    _delegate(
+        &mut contract,
+        acc_b.clone(),
+        acc_c.clone(),
+        vec![tkn_c.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_token_delegate(tkn_a.clone()) == Some(acc_c.clone()));
+    assert!(contract.nft_voting_power(acc_a.clone()) == 0);
+    assert!(contract.nft_voting_power(acc_b.clone()) == 0);
+    assert!(contract.nft_voting_power(acc_c.clone()) == 3);
+    let delegators: HashMap<AccountId, u64> = contract
+        .nft_delegators(acc_c.clone(), None, None)
+        .into_iter()
+        .collect();
+    assert!(delegators.len() == 2);
+    assert!(delegators[&acc_a] == 2);
+    assert!(delegators[&acc_b] == 1);
+
+    // Undelegating gives the votes back to the owner
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_undelegate(vec![tkn_c.clone()]);
+
+    // A transfer clears the delegation of the token
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_b.clone(), None, None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_token_delegate(tkn_b.clone()).is_none());
+    assert!(contract.nft_voting_power(acc_a.clone()) == 0);
+    assert!(contract.nft_voting_power(acc_b.clone()) == 2);
+    assert!(contract.nft_voting_power(acc_c.clone()) == 1);
+    let delegators = contract.nft_delegators(acc_c.clone(), None, None);
+    assert!(delegators == vec![(acc_a.clone(), 1)]);
+    let votes = contract.nft_delegated_votes(acc_a.clone());
+    assert!(votes.given == 1 && votes.received == 0);
+}
+
+#[test]
+fn test_nft_vote_delegate() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+    _delegate(
+        &mut contract,
+        acc_a.clone(),
+        acc_c.clone(),
+        vec![tkn_a.clone()],
+    );
+    _vote(
+        &mut contract,
+        acc_c.clone(),
+        proposal_id,
+        0,
+        vec![tkn_a.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_token_vote(proposal_id, tkn_a.clone()) == Some(0));
+}
+
+#[test]
+#[should_panic(expected = "Only owner or delegate can vote with the token")]
+fn test_nft_vote_panic_delegated() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    let proposal_id = _create_proposal(&mut contract, acc_x.clone());
+    _delegate(
+        &mut contract,
+        acc_a.clone(),
+        acc_c.clone(),
+        vec![tkn_a.clone()],
+    );
+    _vote(
+        &mut contract,
+        acc_a.clone(),
+        proposal_id,
+        0,
+        vec![tkn_a.clone()],
+    );
+}
+
+#[test]
+#[should_panic(expected = "Token is not delegated")]
+fn test_nft_undelegate_panic_not_delegated() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_undelegate(vec![tkn_a.clone()]);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can delegate the token")]
+fn test_nft_delegate_panic_owner() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    _delegate(
+        &mut contract,
+        acc_b.clone(),
+        acc_c.clone(),
+        vec![tkn_a.clone()],
+    );
+}
+
+#[test]
+#[should_panic(expected = "to cover storage")]
+fn test_nft_delegate_panic_storage() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_delegate(acc_c.clone(), vec![tkn_a.clone()]);
+}
+
+#[test]
+fn test_nft_delegate_storage_balance() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    // The delegation is paid from the storage balance
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let initial_storage_usage = env::storage_usage();
+    contract.nft_delegate(acc_c.clone(), vec![tkn_a.clone()]);
+    let cost =
+        Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let balance = contract.storage_balance_of(acc_a.clone()).unwrap();
+    assert!(cost > 0);
+    assert!(balance.total.0 == ONE_NEAR - cost);
+
+    // Undelegating gives the storage back to the balance
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_undelegate(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let balance = contract.storage_balance_of(acc_a.clone()).unwrap();
+    assert!(balance.total.0 == ONE_NEAR);
+}
+
+#[test]
+fn test_nft_delegate_returned_transfer() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_d = AccountId::new_unchecked(String::from("account.delegate"));
+    let acc_r = AccountId::new_unchecked(String::from("account.receiver"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    // The receiver delegates inside nft_on_transfer and then returns the token
+    _transfer_call(&mut contract, tkn_a.clone(), acc_a.clone(), acc_r.clone());
+    _delegate(
+        &mut contract,
+        acc_r.clone(),
+        acc_d.clone(),
+        vec![tkn_a.clone()],
+    );
+    _resolve_returned(&mut contract, tkn_a.clone(), acc_a.clone(), acc_r.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_token_delegate(tkn_a.clone()).is_none());
+    assert!(contract.nft_voting_power(acc_a.clone()) == 1);
+    assert!(contract.nft_voting_power(acc_d.clone()) == 0);
+    assert!(contract.nft_voting_power(acc_r.clone()) == 0);
+
+    // The owner can delegate the returned token again
+    _delegate(
+        &mut contract,
+        acc_a.clone(),
+        acc_c.clone(),
+        vec![tkn_a.clone()],
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_voting_power(acc_a.clone()) == 0);
+    assert!(contract.nft_voting_power(acc_c.clone()) == 1);
+}
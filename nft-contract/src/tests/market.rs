@@ -0,0 +1,363 @@
+use super::*;
+
+use crate::approval::NftApproval;
+use crate::nft_core::NonFungibleTokenCore;
+use crate::royalty::NftRoyalties;
+
+fn _enforce_royalties(contract: &mut Contract, market_id: AccountId) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_add_marketplace(market_id);
+    contract.ctrl_set_royalty_enforced(true);
+}
+
+#[test]
+fn test_nft_royalty_enforced() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _enforce_royalties(&mut contract, acc_m.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_royalty_enforced());
+    assert!(contract.ctrl_is_marketplace(acc_m.clone()));
+    assert!(contract.ctrl_marketplaces(None, None) == vec![acc_m.clone()]);
+
+    // Owner to owner transfers stay free
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_a.clone(), None, None);
+
+    // Allowlisted marketplaces sell through the payout
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_payout(
+        acc_c.clone(),
+        tkn_a.clone(),
+        None,
+        None,
+        U128::from(1000),
+        32,
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.owner_id == acc_c.clone());
+}
+
+#[test]
+#[should_panic(
+    expected = "Only allowlisted marketplaces can be approved while royalties are enforced"
+)]
+fn test_nft_approve_panic_marketplace() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _enforce_royalties(&mut contract, acc_m.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_b.clone(), None);
+}
+
+#[test]
+#[should_panic(expected = "Approved accounts must transfer through nft_transfer_payout")]
+fn test_nft_transfer_panic_enforced() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _enforce_royalties(&mut contract, acc_m.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    // The marketplace can not skip the payout
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_a.clone(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can manage marketplaces")]
+fn test_ctrl_add_marketplace_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_add_marketplace(acc_a.clone());
+}
+
+#[test]
+fn test_nft_min_sale_price_gift() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_min_sale_price(U128::from(ONE_NEAR));
+
+    // The owner gifts the token through the market for free
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_flag_gift(tkn_a.clone(), Some(acc_b.clone()));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_payout(acc_b.clone(), tkn_a.clone(), None, None, U128::from(0), 32);
+
+    // The flag is cleared by the transfer and the gift is not a sale
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_min_sale_price() == U128::from(ONE_NEAR));
+    assert!(contract.nft_gift_receiver(tkn_a.clone()).is_none());
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.owner_id == acc_b.clone());
+    let stats = contract.nft_sale_stats();
+    assert!(stats.count == 0);
+    assert!(stats.volume == U128::from(0));
+    assert!(contract.nft_sale_history(tkn_a.clone()).is_empty());
+}
+
+#[test]
+fn test_nft_transfer_payout_reject_log() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_min_sale_price(U128::from(1000));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        contract.nft_transfer_payout(
+            acc_b.clone(),
+            tkn_a.clone(),
+            None,
+            None,
+            U128::from(999),
+            32,
+        )
+    }));
+    assert!(result.is_err());
+
+    // The rejection is logged before the call panics
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(logs.len() == 1);
+    assert!(logs[0].contains(r#""event":"sale_reject""#));
+    assert!(logs[0].contains(r#""market_id":"account.market""#));
+    assert!(logs[0].contains(r#""price":"999""#));
+}
+
+#[test]
+#[should_panic(expected = "Sale price is below the minimum of 1000 yoctoNEAR")]
+fn test_nft_transfer_payout_panic_price() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_min_sale_price(U128::from(1000));
+
+    // A gift to another receiver does not lift the minimum
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_flag_gift(tkn_a.clone(), Some(acc_c.clone()));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_payout(
+        acc_b.clone(),
+        tkn_a.clone(),
+        None,
+        None,
+        U128::from(999),
+        32,
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only allowlisted marketplaces can sell while royalties are enforced")]
+fn test_nft_transfer_payout_panic_approved_before() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_n = AccountId::new_unchecked(String::from("account.market.n"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    // The approval was given before another marketplace was allowlisted
+    _enforce_royalties(&mut contract, acc_n.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_payout(acc_b.clone(), tkn_a.clone(), None, None, U128::from(0), 32);
+}
+
+#[test]
+#[should_panic(expected = "Only allowlisted marketplaces can sell while royalties are enforced")]
+fn test_nft_transfer_payout_panic_removed_marketplace() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _enforce_royalties(&mut contract, acc_m.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    // The marketplace is dropped while it still holds the approval
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_remove_marketplace(acc_m.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_m.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_payout(acc_b.clone(), tkn_a.clone(), None, None, U128::from(0), 32);
+}
@@ -78,9 +78,12 @@ fn test_nft_metadata_token() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -114,3 +117,319 @@ fn test_nft_metadata_token() {
     assert!(data.metadata.reference.is_none());
     assert!(data.metadata.reference_hash.is_none());
 }
+
+fn _metadata() -> ContractMetadata {
+    ContractMetadata {
+        spec: "nft-1.0.0".to_string(),
+        name: "Auras".to_string(),
+        symbol: "AURA".to_string(),
+        icon: Some("data:image/svg+xml,<svg></svg>".to_string()),
+        base_uri: Some("https://ipfs.io/ipfs".to_string()),
+        reference: Some("https://auras.dao/collection.json".to_string()),
+        reference_hash: Some("4G4fKJVi1GCpTSsdmA3NYFYuJFzLHrP9x5kPbtKjHDUL".to_string()),
+    }
+}
+
+#[test]
+fn test_ctrl_set_metadata() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_metadata(_metadata());
+
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(
+        logs == vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.1.0","event":"contract_metadata_update","data":[{}]}"#
+        ]
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let metadata = contract.nft_metadata();
+    assert!(metadata.icon == _metadata().icon);
+    assert!(metadata.base_uri == _metadata().base_uri);
+    assert!(metadata.reference == _metadata().reference);
+}
+
+#[test]
+#[should_panic(expected = "Metadata reference_hash is required when reference is set")]
+fn test_ctrl_set_metadata_panic_reference() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut metadata = _metadata();
+    metadata.reference_hash = None;
+    contract.ctrl_set_metadata(metadata);
+}
+
+#[test]
+#[should_panic(expected = "Metadata icon must be a data URL")]
+fn test_ctrl_set_metadata_panic_icon() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut metadata = _metadata();
+    metadata.icon = Some("https://auras.dao/icon.png".to_string());
+    contract.ctrl_set_metadata(metadata);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can set metadata")]
+fn test_ctrl_set_metadata_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_metadata(_metadata());
+}
+
+#[test]
+fn test_nft_update_metadata() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_e = AccountId::new_unchecked(String::from("account.evolution"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_e.clone(), Role::Evolver);
+
+    // The evolution contract changes the media of the token
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_e.clone())
+        .attached_deposit(REG_COST)
+        .block_timestamp(200)
+        .is_view(false)
+        .build());
+    contract.nft_update_metadata(
+        tkn_a.clone(),
+        TokenMetadataUpdate {
+            title: None,
+            media: Some("evolved".to_string()),
+            media_hash: Some("evolved_hash".to_string()),
+            attributes: None,
+        },
+    );
+
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(
+        logs == vec![
+            r#"EVENT_JSON:{"standard":"nep171","version":"1.1.0","event":"nft_metadata_update","data":[{"token_ids":["token.a"]}]}"#
+        ]
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.metadata.title == Some(tkn_a.clone()));
+    assert!(token.metadata.media == Some("evolved".to_string()));
+    assert!(token.metadata.media_hash == Some("evolved_hash".to_string()));
+    assert!(token.metadata.issued_at == Some(0));
+    assert!(token.metadata.updated_at == Some(200));
+}
+
+#[test]
+#[should_panic(expected = "Media and media_hash must be updated together")]
+fn test_nft_update_metadata_panic_media() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_update_metadata(
+        tkn_a.clone(),
+        TokenMetadataUpdate {
+            title: None,
+            media: Some("evolved".to_string()),
+            media_hash: None,
+            attributes: None,
+        },
+    );
+}
+
+#[test]
+#[should_panic(expected = "Only owner, admin or evolver can update token metadata")]
+fn test_nft_update_metadata_panic_access() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    // Token owners can not change the metadata
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_update_metadata(
+        tkn_a.clone(),
+        TokenMetadataUpdate {
+            title: Some("renamed".to_string()),
+            media: None,
+            media_hash: None,
+            attributes: None,
+        },
+    );
+}
+
+#[test]
+fn test_nft_metadata_versioned() {
+    use near_sdk::borsh::{BorshDeserialize, BorshSerialize};
+
+    // Entries written before the optional fields existed still load
+    let legacy = TokenMetadataV1 {
+        title: "aa".to_string(),
+        media: "bb".to_string(),
+        media_hash: "cc".to_string(),
+        attributes: "dd".to_string(),
+    };
+    let bytes = legacy.try_to_vec().unwrap();
+    let metadata: TokenMetadata = VersionedTokenMetadata::try_from_slice(&bytes)
+        .unwrap()
+        .into();
+    assert!(metadata.title == "aa");
+    assert!(metadata.attributes == "dd");
+    assert!(metadata.description.is_none());
+
+    // New entries are written with the version tag
+    let current = TokenMetadata {
+        title: "aa".to_string(),
+        media: "bb".to_string(),
+        media_hash: "cc".to_string(),
+        attributes: "dd".to_string(),
+        description: Some("ee".to_string()),
+        reference: Some("ff".to_string()),
+        reference_hash: Some("gg".to_string()),
+    };
+    let bytes = VersionedTokenMetadata::from(current).try_to_vec().unwrap();
+    let metadata: TokenMetadata = VersionedTokenMetadata::try_from_slice(&bytes)
+        .unwrap()
+        .into();
+    assert!(metadata.description == Some("ee".to_string()));
+    assert!(metadata.reference_hash == Some("gg".to_string()));
+}
+
+#[test]
+fn test_nft_register_optional_fields() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    // Old and new shaped input in the same call
+    let token_list: HashMap<String, TokenMetadata> = near_sdk::serde_json::from_str(
+        r#"{
+            "token.a": {"title": "token.a", "media": "bb", "media_hash": "cc", "attributes": "dd"},
+            "token.b": {"title": "token.b", "media": "bb", "media_hash": "cc", "attributes": "dd",
+                "description": "ee", "reference": "ff", "reference_hash": "gg"}
+        }"#,
+    )
+    .unwrap();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST * 2)
+        .is_view(false)
+        .build());
+    contract.nft_register(token_list, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 2);
+
+    for _ in 0..2 {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(acc_a.clone())
+            .attached_deposit(ONE_NEAR * 22)
+            .is_view(false)
+            .build());
+        contract.nft_mint();
+    }
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.metadata.description == Some("An exchange of energies.".to_string()));
+    assert!(token.metadata.reference.is_none());
+
+    let token = contract.nft_token(tkn_b.clone()).expect("must be set");
+    assert!(token.metadata.description == Some("ee".to_string()));
+    assert!(token.metadata.reference == Some("ff".to_string()));
+    assert!(token.metadata.reference_hash == Some("gg".to_string()));
+}
+
+#[test]
+#[should_panic(expected = "Token reference_hash is required when reference is set")]
+fn test_nft_register_panic_reference() {
+    let tkn_a = String::from("token.a");
+
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: Some("ff".to_string()),
+            reference_hash: None,
+        },
+    );
+    contract.nft_register(map, None);
+}
@@ -1,5 +1,7 @@
 use super::*;
 
+use crate::enumeration::NftEnumeration;
+
 fn _mint_token(
     contract: &mut Contract,
     token_id: String,
@@ -19,9 +21,12 @@ fn _mint_token(
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(creator_id.clone())
         .attached_deposit(1)
@@ -81,9 +86,12 @@ fn test_nft_mint_panic_list_cost() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -123,9 +131,12 @@ fn test_nft_mint_panic_public_cost() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -169,9 +180,12 @@ fn test_nft_mint_panic_token_id() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -216,9 +230,12 @@ fn test_nft_mint_panic_access() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -236,7 +253,7 @@ fn test_nft_mint_panic_access() {
 }
 
 #[test]
-#[should_panic(expected = "Must attach 4200000000000000000000 yoctoNEAR to cover storage")]
+#[should_panic(expected = "Must attach 4880000000000000000000 yoctoNEAR to cover storage")]
 fn test_nft_register_panic_cost() {
     let tkn_a = String::from("token.a");
 
@@ -257,9 +274,12 @@ fn test_nft_register_panic_cost() {
             media: "012345678901234567890123456789012".to_string(),
             media_hash: "0123456789012345678901234567890123456789012345678901234567890123".to_string(),
             attributes: "01234567890123456789012345678901234567890123456789012345678901230123456789012345678901234567890123456789012345678901234567890123".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 }
 
 #[test]
@@ -284,10 +304,13 @@ fn test_nft_register_panic_id_used() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map.clone());
-    contract.nft_register(map.clone());
+    contract.nft_register(map.clone(), None);
+    contract.nft_register(map.clone(), None);
 }
 
 /**************/
@@ -318,6 +341,9 @@ fn test_nft_mint_panic_amount() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -327,9 +353,12 @@ fn test_nft_mint_panic_amount() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -371,6 +400,9 @@ fn test_nft_mint_panic_mint_limit() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -380,9 +412,12 @@ fn test_nft_mint_panic_mint_limit() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -427,9 +462,12 @@ fn test_nft_mint_panic_mint_disabled() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -481,6 +519,9 @@ fn test_nft_mint_state() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -490,6 +531,9 @@ fn test_nft_mint_state() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -499,6 +543,9 @@ fn test_nft_mint_state() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -508,6 +555,9 @@ fn test_nft_mint_state() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
     map.insert(
@@ -517,9 +567,12 @@ fn test_nft_mint_state() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
@@ -596,3 +649,72 @@ fn test_nft_mint_state() {
     assert!(state.limit == 0, "unexpected limit");
     assert!(state.tokens.len() == 2, "unexpected tokens");
 }
+
+#[test]
+fn test_nft_mint_gas_flat() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    // Register the tokens in batches like the deploy scripts do
+    for batch in 0..10 {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(acc_x.clone())
+            .attached_deposit(ONE_NEAR)
+            .is_view(false)
+            .build());
+        let mut map = HashMap::new();
+        for index in 0..100 {
+            let token_id = format!("token.{}", batch * 100 + index);
+            map.insert(
+                token_id.clone(),
+                TokenMetadata {
+                    title: token_id,
+                    media: "bb".to_string(),
+                    media_hash: "cc".to_string(),
+                    attributes: "dd".to_string(),
+                    description: None,
+                    reference: None,
+                    reference_hash: None,
+                },
+            );
+        }
+        contract.nft_register(map, None);
+    }
+
+    // Mint through the whole registry, every account mints the default limit of 5
+    let mut gas = vec![];
+    for account in 0..200 {
+        let acc_a = AccountId::new_unchecked(format!("account.{}", account));
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(acc_x.clone())
+            .attached_deposit(1)
+            .is_view(false)
+            .build());
+        contract.nft_allow_minting(acc_a.clone(), 5);
+
+        for _ in 0..5 {
+            testing_env!(VMContextBuilder::new()
+                .predecessor_account_id(acc_a.clone())
+                .attached_deposit(ONE_NEAR * 22)
+                .is_view(false)
+                .build());
+            contract.nft_mint();
+            gas.push(env::used_gas().0);
+        }
+    }
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_total_supply() == U128::from(1000));
+
+    // Compare the first mint of an early and of the last account
+    let first = gas[10];
+    let last = gas[995];
+    assert!(
+        last < first + first / 10,
+        "nft_mint gas grew from {} to {}",
+        first,
+        last
+    );
+}
@@ -1,16 +1,32 @@
 use near_sdk::test_utils::VMContextBuilder;
 use near_sdk::{testing_env, AccountId};
 
+use crate::nft_core::{NonFungibleTokenCore, NonFungibleTokenResolver};
 use crate::*;
 
 const REG_COST: Balance = 4200000000000000000000;
 
 mod approval;
+mod burn;
 mod enumeration;
 mod events;
+mod governance;
+mod market;
 mod metadata;
 mod mint;
+mod ownership;
+mod rarity;
+mod roles;
 mod royalty;
+mod sales;
+mod snapshot;
+mod staking;
+mod storage;
+mod timelock;
+mod traits;
+mod transfer;
+mod treasury;
+mod upgrade;
 
 #[test]
 fn test_nft_approval_allow_access() {
@@ -34,9 +50,12 @@ fn test_nft_approval_allow_access() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     // Test nft_allow_minting: pass
     testing_env!(VMContextBuilder::new()
@@ -122,7 +141,7 @@ fn test_nft_revoke_minting_panic_yocto() {
 /*********************/
 
 #[test]
-#[should_panic(expected = "Only owner can set mint info")]
+#[should_panic(expected = "Only owner or admin can set mint info")]
 fn test_nft_set_mint_state_panic_access() {
     let acc_a = AccountId::new_unchecked(String::from("account.a"));
     let acc_x = AccountId::new_unchecked(String::from("account.x"));
@@ -143,7 +162,7 @@ fn test_nft_set_mint_state_panic_access() {
 }
 
 #[test]
-#[should_panic(expected = "Only owner can allow minting access")]
+#[should_panic(expected = "Only owner or whitelist manager can allow minting access")]
 fn test_nft_allow_minting_panic_access() {
     let acc_a = AccountId::new_unchecked(String::from("account.a"));
     let acc_b = AccountId::new_unchecked(String::from("account.b"));
@@ -161,7 +180,7 @@ fn test_nft_allow_minting_panic_access() {
 }
 
 #[test]
-#[should_panic(expected = "Only owner can revoke minting access")]
+#[should_panic(expected = "Only owner or whitelist manager can revoke minting access")]
 fn test_nft_revoke_minting_panic_access() {
     let acc_a = AccountId::new_unchecked(String::from("account.a"));
     let acc_b = AccountId::new_unchecked(String::from("account.b"));
@@ -179,7 +198,7 @@ fn test_nft_revoke_minting_panic_access() {
 }
 
 #[test]
-#[should_panic(expected = "Only owner can withdrawal funds")]
+#[should_panic(expected = "Only owner or treasurer can withdrawal funds")]
 fn test_ctrl_withdrawal_panic_access() {
     let acc_a = AccountId::new_unchecked(String::from("account.a"));
     let acc_x = AccountId::new_unchecked(String::from("account.x"));
@@ -192,7 +211,7 @@ fn test_ctrl_withdrawal_panic_access() {
         .attached_deposit(1)
         .is_view(false)
         .build());
-    contract.ctrl_withdrawal(U128::from(5));
+    contract.ctrl_withdrawal(U128::from(5), None);
 }
 
 /******************/
@@ -249,9 +268,12 @@ fn test_nft_transfer_panic_owner() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
 
     // Approve interface
     testing_env!(VMContextBuilder::new()
@@ -292,3 +314,116 @@ pub(crate) fn _token_lsit_to_map(list: Vec<JsonToken>) -> HashMap<String, JsonTo
     }
     map
 }
+
+//registers the tokens in one call, the metadata only differs by the title
+pub(crate) fn _register_tokens(contract: &mut Contract, token_ids: Vec<String>) {
+    _register_tokens_with_attributes(
+        contract,
+        token_ids
+            .iter()
+            .map(|token_id| (token_id.as_str(), "dd"))
+            .collect(),
+    );
+}
+
+//registers the tokens with their attributes in one call
+pub(crate) fn _register_tokens_with_attributes(contract: &mut Contract, tokens: Vec<(&str, &str)>) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(REG_COST * tokens.len() as u128)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    for (token_id, attributes) in tokens {
+        map.insert(
+            token_id.to_string(),
+            TokenMetadata {
+                title: token_id.to_string(),
+                media: "bb".to_string(),
+                media_hash: "cc".to_string(),
+                attributes: attributes.to_string(),
+                description: None,
+                reference: None,
+                reference_hash: None,
+            },
+        );
+    }
+    contract.nft_register(map, None);
+}
+
+//registers a token with the attributes and mints it for the owner
+pub(crate) fn _mint_token_with_attributes(
+    contract: &mut Contract,
+    token_id: String,
+    attributes: &str,
+    owner_id: AccountId,
+) {
+    _register_tokens_with_attributes(contract, vec![(token_id.as_str(), attributes)]);
+    _mint_next_token(contract, owner_id);
+}
+
+//allows the owner to mint once and mints the next registered token
+pub(crate) fn _mint_next_token(contract: &mut Contract, owner_id: AccountId) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(owner_id.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(owner_id.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+}
+
+//registers a token and mints it for the owner
+pub(crate) fn _mint_token(contract: &mut Contract, token_id: String, owner_id: AccountId) {
+    _mint_token_with_attributes(contract, token_id, "dd", owner_id);
+}
+
+//sends the token with nft_transfer_call, the receiver acts while holding it before the resolver runs
+pub(crate) fn _transfer_call(
+    contract: &mut Contract,
+    token_id: String,
+    owner_id: AccountId,
+    receiver_id: AccountId,
+) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(owner_id)
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer_call(receiver_id, token_id, None, None, "".to_string());
+}
+
+//resolves a nft_transfer_call whose receiver asked for the token back
+pub(crate) fn _resolve_returned(
+    contract: &mut Contract,
+    token_id: String,
+    owner_id: AccountId,
+    receiver_id: AccountId,
+) {
+    let contract_id = AccountId::new_unchecked(String::from("account.contract"));
+    testing_env!(
+        VMContextBuilder::new()
+            .current_account_id(contract_id.clone())
+            .predecessor_account_id(contract_id)
+            .is_view(false)
+            .build(),
+        near_sdk::VMConfig::test(),
+        near_sdk::RuntimeFeesConfig::test(),
+        Default::default(),
+        vec![near_sdk::PromiseResult::Successful(b"true".to_vec())],
+    );
+    assert!(!contract.nft_resolve_transfer(
+        None,
+        owner_id,
+        receiver_id,
+        token_id,
+        HashMap::new(),
+        None
+    ));
+}
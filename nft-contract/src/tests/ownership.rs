@@ -0,0 +1,149 @@
+use super::*;
+
+use crate::royalty::NftRoyalties;
+
+#[test]
+fn test_ctrl_ownership_transfer() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    // Propose the new owner
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_propose_owner(acc_d.clone());
+
+    // Nothing changes until the proposal is accepted
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_owner() == acc_x.clone());
+    assert!(contract.ctrl_pending_owner() == Some(acc_d.clone()));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_d.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_accept_ownership();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_owner() == acc_d.clone());
+    assert!(contract.ctrl_pending_owner().is_none());
+
+    // Royalties follow the current owner
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.royalty.get(&acc_d.clone()).expect("must be set") == &(600));
+    assert!(!token.royalty.contains_key(&acc_x));
+
+    let payout = contract.nft_payout(tkn_a.clone(), U128::from(100000), 32);
+    assert!(payout.payout.get(&acc_d.clone()).expect("must be set") == &U128::from(6000));
+    assert!(!payout.payout.contains_key(&acc_x));
+}
+
+#[test]
+fn test_ctrl_royalty_account() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_t = AccountId::new_unchecked(String::from("account.treasury"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_royalty_account(Some(acc_t.clone()));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_royalty_account() == acc_t.clone());
+
+    let payout = contract.nft_payout(tkn_a.clone(), U128::from(100000), 32);
+    assert!(payout.payout.get(&acc_t.clone()).expect("must be set") == &U128::from(6000));
+    assert!(!payout.payout.contains_key(&acc_x));
+
+    // Clearing the account falls back to the owner
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_royalty_account(None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_royalty_account() == acc_x.clone());
+}
+
+#[test]
+#[should_panic(expected = "Only the proposed owner can accept ownership")]
+fn test_ctrl_accept_ownership_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_propose_owner(acc_d.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_accept_ownership();
+}
+
+#[test]
+#[should_panic(expected = "No ownership transfer is pending")]
+fn test_ctrl_accept_ownership_panic_cancelled() {
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_propose_owner(acc_d.clone());
+    contract.ctrl_cancel_ownership();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_d.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_accept_ownership();
+}
+
+#[test]
+#[should_panic(expected = "Only owner can propose a new owner")]
+fn test_ctrl_propose_owner_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_propose_owner(acc_a.clone());
+}
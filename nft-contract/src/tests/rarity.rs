@@ -0,0 +1,177 @@
+use super::*;
+
+fn _compute_rarity(contract: &mut Contract, limit: u64) -> Vec<RarityPhase> {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_start_rarity();
+
+    let mut phases = vec![];
+    loop {
+        let progress = contract.ctrl_compute_rarity(Some(limit));
+        phases.push(progress.phase.clone());
+        if progress.phase == RarityPhase::Done {
+            return phases;
+        }
+    }
+}
+
+#[test]
+fn test_nft_rarity() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _register_tokens_with_attributes(
+        &mut contract,
+        vec![
+            ("token.a", r#"{"Background":"Void","Eyes":"Gold"}"#),
+            ("token.b", r#"{"Background":"Void","Eyes":"Blue"}"#),
+            ("token.c", r#"{"Background":"Red","Eyes":"Blue"}"#),
+            ("token.d", r#"{"Background":"Void","Eyes":"Blue"}"#),
+        ],
+    );
+
+    // Each pass takes two calls with a limit of 3
+    let phases = _compute_rarity(&mut contract, 3);
+    assert!(
+        phases
+            == vec![
+                RarityPhase::Count,
+                RarityPhase::Score,
+                RarityPhase::Score,
+                RarityPhase::Rank,
+                RarityPhase::Rank,
+                RarityPhase::Done,
+            ]
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let frequencies = contract.nft_trait_frequencies();
+    assert!(frequencies["Background"]["Void"] == 3);
+    assert!(frequencies["Background"]["Red"] == 1);
+    assert!(frequencies["Eyes"]["Gold"] == 1);
+    assert!(frequencies["Eyes"]["Blue"] == 3);
+
+    // Equal scores are ranked by token ID
+    let rarity = contract
+        .nft_rarity("token.a".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 5333);
+    assert!(rarity.rank == 1);
+    let rarity = contract
+        .nft_rarity("token.c".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 5333);
+    assert!(rarity.rank == 2);
+    let rarity = contract
+        .nft_rarity("token.b".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 2666);
+    assert!(rarity.rank == 3);
+    let rarity = contract
+        .nft_rarity("token.d".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 2666);
+    assert!(rarity.rank == 4);
+    assert!(contract.nft_rarity("token.e".to_string()).is_none());
+}
+
+#[test]
+fn test_nft_rarity_recompute() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _register_tokens_with_attributes(
+        &mut contract,
+        vec![
+            ("token.a", r#"{"Background":"Void"}"#),
+            ("token.b", r#"{"Background":"Red"}"#),
+        ],
+    );
+    _compute_rarity(&mut contract, 50);
+
+    // A second run replaces the scores with the new frequencies
+    _register_tokens_with_attributes(
+        &mut contract,
+        vec![
+            ("token.c", r#"{"Background":"Red"}"#),
+            ("token.d", r#"{"Background":"Red"}"#),
+        ],
+    );
+    _compute_rarity(&mut contract, 50);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let frequencies = contract.nft_trait_frequencies();
+    assert!(frequencies["Background"]["Void"] == 1);
+    assert!(frequencies["Background"]["Red"] == 3);
+
+    let rarity = contract
+        .nft_rarity("token.a".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 4000);
+    assert!(rarity.rank == 1);
+    let rarity = contract
+        .nft_rarity("token.d".to_string())
+        .expect("must be set");
+    assert!(rarity.score == 1333);
+    assert!(rarity.rank == 4);
+    assert!(contract.rarity_ranking.len() == 4);
+}
+
+#[test]
+#[should_panic(expected = "Registered tokens changed, rarity computation has to be started again")]
+fn test_ctrl_compute_rarity_panic_registered() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _register_tokens_with_attributes(&mut contract, vec![("token.a", r#"{"Background":"Void"}"#)]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_start_rarity();
+
+    _register_tokens_with_attributes(&mut contract, vec![("token.b", r#"{"Background":"Red"}"#)]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_compute_rarity(None);
+}
+
+#[test]
+#[should_panic(expected = "Rarity computation has not been started")]
+fn test_ctrl_compute_rarity_panic_started() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_compute_rarity(None);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can compute rarity")]
+fn test_ctrl_start_rarity_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_start_rarity();
+}
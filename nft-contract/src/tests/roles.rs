@@ -0,0 +1,194 @@
+use super::*;
+
+#[test]
+fn test_ctrl_roles() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    // Grant roles as owner
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_a.clone(), Role::Admin);
+    contract.ctrl_grant_role(acc_b.clone(), Role::WhitelistManager);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_has_role(acc_x.clone(), Role::Treasurer));
+    assert!(contract.ctrl_has_role(acc_a.clone(), Role::Admin));
+    assert!(!contract.ctrl_has_role(acc_a.clone(), Role::Minter));
+    assert!(contract.ctrl_roles_for(acc_b.clone()) == vec![Role::WhitelistManager]);
+    assert!(contract.ctrl_roles(None, None).len() == 2);
+
+    // Admins can manage the other roles
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_b.clone(), Role::Minter);
+    contract.ctrl_revoke_role(acc_b.clone(), Role::WhitelistManager);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_roles_for(acc_b.clone()) == vec![Role::Minter]);
+
+    // Whitelist managers can only manage the whitelist
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_b.clone(), Role::WhitelistManager);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 3);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_mint_state(acc_a.clone(), None, None).limit == 3);
+
+    // Revoking the last role removes the account
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_revoke_role(acc_a.clone(), Role::Admin);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_roles_for(acc_a.clone()).is_empty());
+    assert!(contract.ctrl_roles(None, None).len() == 1);
+}
+
+#[test]
+#[should_panic(expected = "Minting is paused")]
+fn test_nft_mint_panic_paused() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_b.clone(), Role::Pauser);
+    contract.nft_allow_minting(acc_a.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_set_mint_paused(true);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+}
+
+/*********************/
+/* Access Role Only  */
+/*********************/
+
+#[test]
+#[should_panic(expected = "Only owner can manage the admin role")]
+fn test_ctrl_grant_role_panic_admin() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_a.clone(), Role::Admin);
+
+    // Admins cannot create other admins
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_b.clone(), Role::Admin);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can manage the treasurer role")]
+fn test_ctrl_grant_role_panic_treasurer() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_a.clone(), Role::Admin);
+
+    // Admins cannot give themselves access to the funds
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_a.clone(), Role::Treasurer);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can manage roles")]
+fn test_ctrl_grant_role_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_b.clone(), Role::Minter);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or treasurer can withdrawal funds")]
+fn test_ctrl_withdrawal_panic_role() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    // A whitelist manager is not a treasurer
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_grant_role(acc_a.clone(), Role::WhitelistManager);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128::from(5), None);
+}
@@ -1,6 +1,6 @@
 use super::*;
 
-use crate::royalty::NftRoyalties;
+use crate::royalty::{NftRoyalties, _compute_payout};
 
 #[test]
 fn test_nft_royalty() {
@@ -25,9 +25,12 @@ fn test_nft_royalty() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
         .attached_deposit(1)
@@ -80,6 +83,295 @@ fn test_nft_royalty() {
     assert!(token.royalty.get(&acc_x.clone()).expect("must be set") == &(600));
 }
 
+#[test]
+fn test_ctrl_set_royalties() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_r = AccountId::new_unchecked(String::from("account.artist"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    contract.nft_register(map, None);
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+
+    // Split the royalties between the dao and the artist
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_d.clone(), 400);
+    royalties.insert(acc_r.clone(), 250);
+    contract.ctrl_set_royalties(Some(royalties));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_royalties().len() == 2);
+
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.royalty.get(&acc_d.clone()).expect("must be set") == &(400));
+    assert!(token.royalty.get(&acc_r.clone()).expect("must be set") == &(250));
+    assert!(!token.royalty.contains_key(&acc_x));
+
+    let payout = contract.nft_payout(tkn_a.clone(), U128::from(100000), 32);
+    assert!(payout.payout.get(&acc_d.clone()).expect("must be set") == &U128::from(4000));
+    assert!(payout.payout.get(&acc_r.clone()).expect("must be set") == &U128::from(2500));
+    assert!(payout.payout.get(&acc_a.clone()).expect("must be set") == &U128::from(93500));
+
+    // Clearing the table restores the default royalty
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_royalties(None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let royalties = contract.nft_royalties();
+    assert!(royalties.len() == 1);
+    assert!(royalties.get(&acc_x.clone()).expect("must be set") == &(600));
+}
+
+#[test]
+#[should_panic(expected = "Royalties must total less than 10000 basis points")]
+fn test_ctrl_set_royalties_panic_total() {
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_d.clone(), 6000);
+    royalties.insert(acc_x.clone(), 4000);
+    contract.ctrl_set_royalties(Some(royalties));
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can set royalties")]
+fn test_ctrl_set_royalties_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_a.clone(), 500);
+    contract.ctrl_set_royalties(Some(royalties));
+}
+
+#[test]
+fn test_nft_token_royalties() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_g = AccountId::new_unchecked(String::from("account.guest"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    // Register a guest artist piece next to a regular one
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST * 2)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    for token_id in [tkn_a.clone(), tkn_b.clone()].iter() {
+        map.insert(
+            token_id.clone(),
+            TokenMetadata {
+                title: token_id.clone(),
+                media: "bb".to_string(),
+                media_hash: "cc".to_string(),
+                attributes: "dd".to_string(),
+                description: None,
+                reference: None,
+                reference_hash: None,
+            },
+        );
+    }
+    let mut guest = HashMap::new();
+    guest.insert(acc_g.clone(), 500);
+    let mut royalties = HashMap::new();
+    royalties.insert(tkn_b.clone(), guest);
+    contract.nft_register(map, Some(royalties));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 2);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+    contract.nft_mint();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let custom = contract.nft_custom_royalties(None, None);
+    assert!(custom.len() == 1);
+    assert!(custom.contains_key(&tkn_b));
+
+    // Only the guest piece pays the guest artist
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.royalty.len() == 1);
+    let token = contract.nft_token(tkn_b.clone()).expect("must be set");
+    assert!(token.royalty.get(&acc_g.clone()).expect("must be set") == &(500));
+    assert!(token.royalty.get(&acc_x.clone()).expect("must be set") == &(600));
+
+    let payout = contract.nft_payout(tkn_b.clone(), U128::from(100000), 32);
+    assert!(payout.payout.get(&acc_g.clone()).expect("must be set") == &U128::from(5000));
+    assert!(payout.payout.get(&acc_x.clone()).expect("must be set") == &U128::from(6000));
+    assert!(payout.payout.get(&acc_a.clone()).expect("must be set") == &U128::from(89000));
+}
+
+#[test]
+#[should_panic(expected = "Royalties must total less than 10000 basis points")]
+fn test_ctrl_set_royalties_panic_token_total() {
+    let tkn_a = String::from("token.a");
+
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_g = AccountId::new_unchecked(String::from("account.guest"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST * 2)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    let mut guest = HashMap::new();
+    guest.insert(acc_g.clone(), 5000);
+    let mut royalties = HashMap::new();
+    royalties.insert(tkn_a.clone(), guest);
+    contract.nft_register(map, Some(royalties));
+
+    // The collection table has to leave room for the creator royalty
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_d.clone(), 5000);
+    contract.ctrl_set_royalties(Some(royalties));
+}
+
+#[test]
+#[should_panic(expected = "Royalties must total less than 10000 basis points")]
+fn test_ctrl_set_royalties_panic_default_total() {
+    let tkn_a = String::from("token.a");
+
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_g = AccountId::new_unchecked(String::from("account.guest"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_d.clone(), 100);
+    contract.ctrl_set_royalties(Some(royalties));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST * 2)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    let mut guest = HashMap::new();
+    guest.insert(acc_g.clone(), 9500);
+    let mut royalties = HashMap::new();
+    royalties.insert(tkn_a.clone(), guest);
+    contract.nft_register(map, Some(royalties));
+
+    // The default royalty also has to leave room for the creator royalty
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_royalties(None);
+}
+
 #[test]
 #[should_panic(expected = "Token not found")]
 fn test_nft_payouts_panic_token() {
@@ -119,9 +411,12 @@ fn test_nft_payouts_panic_count() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
         .attached_deposit(1)
@@ -170,9 +465,12 @@ fn test_nft_transfer_payouts_panic_count() {
             media: "bb".to_string(),
             media_hash: "cc".to_string(),
             attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
         },
     );
-    contract.nft_register(map);
+    contract.nft_register(map, None);
     testing_env!(VMContextBuilder::new()
         .predecessor_account_id(acc_x.clone())
         .attached_deposit(1)
@@ -208,3 +506,99 @@ fn test_nft_transfer_payouts_panic_count() {
         0,
     );
 }
+
+/*******************/
+/* Payout Property */
+/*******************/
+
+//small xorshift generator so the random cases are the same on every run
+fn _next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+#[test]
+fn test_nft_payout_property_sum() {
+    let mut seed = 0x2545_f491_4f6c_dd1d;
+
+    for _ in 0..500 {
+        let balance = (_next_random(&mut seed) as u128) << (_next_random(&mut seed) % 64)
+            | _next_random(&mut seed) as u128;
+
+        //random royalty table, the owner may be one of the recipients
+        let count = _next_random(&mut seed) % (MAX_ROYALTY_ACCOUNTS as u64 + 1);
+        let mut royalties = HashMap::new();
+        let mut remaining = 9999;
+        for index in 0..count {
+            if remaining == 0 {
+                break;
+            }
+            let royalty = (_next_random(&mut seed) % remaining.min(2000)) as u32 + 1;
+            remaining -= royalty as u64;
+            royalties.insert(
+                AccountId::new_unchecked(format!("account.{}", index)),
+                royalty,
+            );
+        }
+        let owner_id = AccountId::new_unchecked(format!(
+            "account.{}",
+            _next_random(&mut seed) % (MAX_ROYALTY_ACCOUNTS as u64 * 2)
+        ));
+
+        let mut accounts: Vec<AccountId> = royalties.keys().cloned().collect();
+        accounts.push(owner_id.clone());
+        accounts.sort();
+        accounts.dedup();
+
+        let payout = _compute_payout(
+            owner_id.clone(),
+            royalties.clone(),
+            balance,
+            accounts.len() as u32,
+        );
+
+        // Every account gets a single entry and the total matches the balance
+        assert!(payout.payout.len() == accounts.len());
+        assert!(payout.payout.values().map(|amount| amount.0).sum::<u128>() == balance);
+
+        // Royalty accounts never receive more than their share
+        for (account_id, royalty) in royalties.iter() {
+            if account_id != &owner_id {
+                let amount = payout.payout.get(account_id).expect("must be set").0;
+                assert!(amount == royalty_to_payout(*royalty, balance).0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_nft_payout_merge_owner() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_x.clone(), 600);
+    royalties.insert(acc_d.clone(), 333);
+
+    // The owner is also a royalty account and gets the rounding remainder
+    let payout = _compute_payout(acc_x.clone(), royalties, 1001, 2);
+    assert!(payout.payout.len() == 2);
+    assert!(payout.payout.get(&acc_d.clone()).expect("must be set") == &U128::from(33));
+    assert!(payout.payout.get(&acc_x.clone()).expect("must be set") == &U128::from(968));
+}
+
+#[test]
+#[should_panic(expected = "Market cannot payout royalties to 3 accounts")]
+fn test_nft_payout_panic_length() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_d = AccountId::new_unchecked(String::from("account.dao"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut royalties = HashMap::new();
+    royalties.insert(acc_x.clone(), 600);
+    royalties.insert(acc_d.clone(), 400);
+
+    _compute_payout(acc_a.clone(), royalties, 100000, 2);
+}
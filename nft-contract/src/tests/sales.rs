@@ -0,0 +1,108 @@
+use super::*;
+
+use crate::royalty::NftRoyalties;
+
+#[test]
+fn test_nft_sale_history() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    contract.nft_register(map, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+
+    // Approve the market and let it sell the token back and forth
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    for index in 0..12 {
+        let (seller, buyer) = if index % 2 == 0 {
+            (acc_a.clone(), acc_b.clone())
+        } else {
+            (acc_b.clone(), acc_a.clone())
+        };
+        if index > 0 {
+            testing_env!(VMContextBuilder::new()
+                .predecessor_account_id(seller.clone())
+                .attached_deposit(ONE_NEAR)
+                .is_view(false)
+                .build());
+            contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+        }
+
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(acc_m.clone())
+            .attached_deposit(1)
+            .block_timestamp(index)
+            .is_view(false)
+            .build());
+        contract.nft_transfer_payout(
+            buyer.clone(),
+            tkn_a.clone(),
+            None,
+            None,
+            U128::from(1000 + index as u128),
+            32,
+        );
+    }
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let stats = contract.nft_sale_stats();
+    assert!(stats.count == 12);
+    assert!(stats.volume == U128::from(12066));
+    assert!(stats.last_price == U128::from(1011));
+
+    // Only the most recent sales are kept
+    let history = contract.nft_sale_history(tkn_a.clone());
+    assert!(history.len() == MAX_SALE_HISTORY);
+    assert!(history[0].price == U128::from(1002));
+    assert!(history[0].timestamp == 2);
+
+    let last = history.last().expect("must be set");
+    assert!(last.seller_id == acc_b.clone());
+    assert!(last.buyer_id == acc_a.clone());
+    assert!(last.market_id == acc_m.clone());
+
+    let sales = contract.nft_last_sales(None, None);
+    assert!(sales.get(&tkn_a.clone()).expect("must be set").price == U128::from(1011));
+}
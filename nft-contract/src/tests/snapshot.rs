@@ -0,0 +1,138 @@
+use super::*;
+
+use crate::nft_core::NonFungibleTokenCore;
+
+fn _transfer(
+    contract: &mut Contract,
+    sender_id: AccountId,
+    receiver_id: AccountId,
+    token_id: String,
+) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(sender_id)
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(receiver_id, token_id, None, None);
+}
+
+fn _take_snapshot(contract: &mut Contract) -> u64 {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .block_index(42)
+        .is_view(false)
+        .build());
+    contract.ctrl_take_snapshot()
+}
+
+fn _to_map(list: Vec<(AccountId, u64)>) -> HashMap<AccountId, u64> {
+    list.into_iter().collect()
+}
+
+#[test]
+fn test_nft_holders() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_c.clone(), acc_b.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_holder_count() == 2);
+    let holders = _to_map(contract.nft_holders(None, None));
+    assert!(holders.len() == 2);
+    assert!(holders[&acc_a] == 2);
+    assert!(holders[&acc_b] == 1);
+    assert!(contract.nft_holders(Some(U128(1)), Some(5)).len() == 1);
+}
+
+#[test]
+fn test_nft_snapshots() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_c.clone(), acc_b.clone());
+
+    assert!(_take_snapshot(&mut contract) == 1);
+    _transfer(&mut contract, acc_a.clone(), acc_c.clone(), tkn_a.clone());
+    _transfer(&mut contract, acc_b.clone(), acc_c.clone(), tkn_c.clone());
+
+    assert!(_take_snapshot(&mut contract) == 2);
+    _transfer(&mut contract, acc_a.clone(), acc_b.clone(), tkn_b.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let snapshot = contract.nft_snapshot(1).expect("must be set");
+    assert!(snapshot.block_height == 42);
+    assert!(snapshot.holder_count == 2);
+    assert!(snapshot.total_supply == 3);
+    assert!(contract.nft_snapshot(0).is_none());
+    assert!(contract.nft_snapshot(3).is_none());
+
+    // Balances keep the value they had when the snapshot was taken
+    assert!(contract.nft_supply_for_owner_at(1, acc_a.clone()) == U128(2));
+    assert!(contract.nft_supply_for_owner_at(1, acc_b.clone()) == U128(1));
+    assert!(contract.nft_supply_for_owner_at(1, acc_c.clone()) == U128(0));
+    assert!(contract.nft_supply_for_owner_at(2, acc_a.clone()) == U128(1));
+    assert!(contract.nft_supply_for_owner_at(2, acc_b.clone()) == U128(0));
+    assert!(contract.nft_supply_for_owner_at(2, acc_c.clone()) == U128(2));
+
+    // Accounts that sold out are still listed for the snapshots they held tokens in
+    let holders = _to_map(contract.nft_holders_at(1, None, None));
+    assert!(holders.len() == 2);
+    assert!(holders[&acc_a] == 2);
+    assert!(holders[&acc_b] == 1);
+    let holders = _to_map(contract.nft_holders_at(2, None, None));
+    assert!(holders.len() == 2);
+    assert!(holders[&acc_a] == 1);
+    assert!(holders[&acc_c] == 2);
+
+    let holders = _to_map(contract.nft_holders(None, None));
+    assert!(holders.len() == 2);
+    assert!(holders[&acc_b] == 1);
+    assert!(holders[&acc_c] == 2);
+}
+
+#[test]
+#[should_panic(expected = "Snapshot not found")]
+fn test_nft_supply_for_owner_at_panic_snapshot() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    contract.nft_supply_for_owner_at(1, acc_a.clone());
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can take snapshots")]
+fn test_ctrl_take_snapshot_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_take_snapshot();
+}
@@ -0,0 +1,314 @@
+use super::*;
+
+use crate::approval::NftApproval;
+use crate::nft_core::NonFungibleTokenCore;
+use crate::storage::StorageManagement;
+
+const SECOND: u64 = 1_000_000_000;
+
+fn _set_rates(contract: &mut Contract) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut element = HashMap::new();
+    element.insert("Fire".to_string(), 3);
+    let mut trait_rates = HashMap::new();
+    trait_rates.insert("Element".to_string(), element);
+    contract.ctrl_set_staking_config(StakingConfig {
+        token_rate: 2,
+        trait_rates,
+    });
+}
+
+#[test]
+fn test_nft_staking() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(
+        &mut contract,
+        tkn_a.clone(),
+        r#"{"Element":"Fire"}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        tkn_b.clone(),
+        r#"{"Element":"Water"}"#,
+        acc_a.clone(),
+    );
+    _set_rates(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .block_timestamp(0)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone(), tkn_b.clone()]);
+
+    // The trait rate is added to the token rate
+    testing_env!(VMContextBuilder::new()
+        .block_timestamp(10 * SECOND)
+        .is_view(true)
+        .build());
+    let stake = contract
+        .nft_stake_status(tkn_a.clone())
+        .expect("must be set");
+    assert!(stake.owner_id == acc_a.clone());
+    assert!(stake.rate == 5);
+    assert!(stake.unclaimed == U128::from(50));
+    let stake = contract
+        .nft_stake_status(tkn_b.clone())
+        .expect("must be set");
+    assert!(stake.rate == 2);
+    assert!(
+        contract
+            .nft_stakes_for_owner(acc_a.clone(), None, None)
+            .len()
+            == 2
+    );
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .block_timestamp(10 * SECOND)
+        .is_view(false)
+        .build());
+    assert!(contract.nft_claim_points(vec![tkn_a.clone(), tkn_b.clone()]) == U128::from(70));
+
+    // Unstaking collects the remaining points and unlocks the token
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .block_timestamp(20 * SECOND)
+        .is_view(false)
+        .build());
+    contract.nft_unstake(vec![tkn_b.clone()]);
+
+    testing_env!(VMContextBuilder::new()
+        .block_timestamp(20 * SECOND)
+        .is_view(true)
+        .build());
+    assert!(contract.nft_points(acc_a.clone()) == U128::from(90));
+    assert!(contract.nft_stake_status(tkn_b.clone()).is_none());
+    let stake = contract
+        .nft_stake_status(tkn_a.clone())
+        .expect("must be set");
+    assert!(stake.unclaimed == U128::from(50));
+}
+
+#[test]
+#[should_panic(expected = "Token is staked and locked")]
+fn test_nft_transfer_panic_staked() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(&mut contract, tkn_a.clone(), "dd", acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_a.clone(), None, None);
+}
+
+#[test]
+#[should_panic(expected = "Token is staked and locked")]
+fn test_nft_approve_panic_staked() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(&mut contract, tkn_a.clone(), "dd", acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can stake the token")]
+fn test_nft_stake_panic_owner() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(&mut contract, tkn_a.clone(), "dd", acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+}
+
+#[test]
+fn test_nft_stake_rate_saturates() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(
+        &mut contract,
+        tkn_a.clone(),
+        r#"{"Element":"Fire"}"#,
+        acc_a.clone(),
+    );
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let mut element = HashMap::new();
+    element.insert("Fire".to_string(), 3);
+    let mut trait_rates = HashMap::new();
+    trait_rates.insert("Element".to_string(), element);
+    contract.ctrl_set_staking_config(StakingConfig {
+        token_rate: u32::MAX,
+        trait_rates,
+    });
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let stake = contract
+        .nft_stake_status(tkn_a.clone())
+        .expect("must be set");
+    assert!(stake.rate == u32::MAX);
+}
+
+#[test]
+#[should_panic(expected = "to cover storage")]
+fn test_nft_stake_panic_storage() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(&mut contract, tkn_a.clone(), "dd", acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+}
+
+#[test]
+fn test_nft_stake_returned_transfer() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_r = AccountId::new_unchecked(String::from("account.receiver"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    // The receiver stakes inside nft_on_transfer and then returns the token
+    _transfer_call(&mut contract, tkn_a.clone(), acc_a.clone(), acc_r.clone());
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_r.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+    _resolve_returned(&mut contract, tkn_a.clone(), acc_a.clone(), acc_r.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_stake_status(tkn_a.clone()).is_none());
+
+    // The owner can move the returned token
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_transfer(acc_b.clone(), tkn_a.clone(), None, None);
+}
+
+#[test]
+fn test_nft_unstake_storage_balance() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_stake(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.storage_balance_of(acc_a.clone()).unwrap().total.0 < ONE_NEAR);
+
+    // Unstaking gives the storage back to the balance
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_unstake(vec![tkn_a.clone()]);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.storage_balance_of(acc_a.clone()).unwrap().total.0 == ONE_NEAR);
+}
@@ -0,0 +1,238 @@
+use super::*;
+
+use crate::approval::NftApproval;
+use crate::storage::StorageManagement;
+
+#[test]
+fn test_storage_deposit() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let min = contract.storage_balance_bounds().min.0;
+    assert!(contract.storage_balance_of(acc_a.clone()).is_none());
+
+    // Deposits can be made for another account
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(min + 1000)
+        .is_view(false)
+        .build());
+    let balance = contract.storage_deposit(Some(acc_a.clone()), None);
+    assert!(balance.total == U128(min + 1000));
+    assert!(balance.available == U128(1000));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let balance = contract.storage_withdraw(Some(U128(400)));
+    assert!(balance.available == U128(600));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_treasury().storage_deposits == U128(min + 600));
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    assert!(contract.storage_unregister(None));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.storage_balance_of(acc_a.clone()).is_none());
+    assert!(contract.ctrl_treasury().storage_deposits == U128(0));
+}
+
+#[test]
+fn test_nft_approve_storage_balance() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    contract.nft_register(map, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    // The storage comes from the balance, its share of the mint deposit is refunded
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    let initial_storage_usage = env::storage_usage();
+    contract.nft_mint();
+    let cost =
+        Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let after_mint = contract.storage_balance_of(acc_a.clone()).unwrap().total.0;
+    assert!(cost > 0);
+    assert!(after_mint == ONE_NEAR - cost);
+    assert!(contract.ctrl_treasury().mint_revenue == U128(ONE_NEAR * 22 - cost));
+
+    // One yocto is enough to approve with a storage balance
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_a.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let cost = Balance::from(bytes_for_approved_account_id(&acc_m)) * env::storage_byte_cost();
+    let after_approve = contract.storage_balance_of(acc_a.clone()).unwrap().total.0;
+    assert!(after_approve == after_mint - cost);
+}
+
+#[test]
+#[should_panic(expected = "Amount exceeds the available storage balance")]
+fn test_storage_withdraw_panic_amount() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.storage_withdraw(Some(U128(ONE_NEAR)));
+}
+
+#[test]
+#[should_panic(expected = "Can not unregister an account that owns tokens")]
+fn test_storage_unregister_panic_tokens() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.storage_unregister(None);
+}
+
+#[test]
+#[should_panic(expected = "Force unregister is not supported, transfer the tokens first")]
+fn test_storage_unregister_panic_force() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.storage_unregister(Some(true));
+}
+
+#[test]
+fn test_nft_mint_public_storage_balance() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _register_tokens(&mut contract, vec![String::from("token.a")]);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_set_mint_info(MintInfo {
+        limit: 5,
+        public: 10,
+        listed: 22,
+    });
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.storage_deposit(None, None);
+
+    // The new mint counter of a public minter is paid with the token
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 10)
+        .is_view(false)
+        .build());
+    let initial_storage_usage = env::storage_usage();
+    contract.nft_mint();
+    let cost =
+        Balance::from(env::storage_usage() - initial_storage_usage) * env::storage_byte_cost();
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let balance = contract.storage_balance_of(acc_a.clone()).unwrap();
+    assert!(balance.total.0 == ONE_NEAR - cost);
+}
@@ -0,0 +1,250 @@
+use super::*;
+
+const DAY: u64 = 86_400_000_000_000;
+
+fn _enable_timelock(contract: &mut Contract) {
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(contract.owner_id.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_timelock(TimelockConfig {
+        delay: DAY,
+        withdrawal_threshold: U128(ONE_NEAR * 10),
+    });
+}
+
+#[test]
+fn test_ctrl_timelock_queue() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    // Queue a price change
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY)
+        .is_view(false)
+        .build());
+    let action_id = contract.ctrl_queue_action(TimelockAction::SetMintInfo {
+        info: MintInfo {
+            limit: 3,
+            public: 40,
+            listed: 30,
+        },
+    });
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let pending = contract.ctrl_pending_actions(None, None);
+    assert!(pending.len() == 1);
+    assert!(pending[0].action_id == action_id);
+    assert!(pending[0].eta == DAY * 2);
+    assert!(contract.nft_mint_info().public == 0);
+
+    // Execute once the delay has passed
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY * 2)
+        .is_view(false)
+        .build());
+    contract.ctrl_execute_action(action_id);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_pending_actions(None, None).is_empty());
+    assert!(contract.nft_mint_info().public == 40);
+    assert!(contract.nft_mint_info().limit == 3);
+}
+
+#[test]
+fn test_ctrl_timelock_cancel() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let action_id = contract.ctrl_queue_action(TimelockAction::GrantRole {
+        account_id: acc_a.clone(),
+        role: Role::Treasurer,
+    });
+    contract.ctrl_cancel_action(action_id);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.ctrl_pending_action(action_id).is_none());
+    assert!(!contract.ctrl_has_role(acc_a.clone(), Role::Treasurer));
+}
+
+#[test]
+fn test_ctrl_timelock_withdrawal_threshold() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    // Small withdrawals do not need to wait
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR), None);
+}
+
+#[test]
+#[should_panic(expected = "Withdrawals above the threshold must be queued through the timelock")]
+fn test_ctrl_withdrawal_panic_timelock() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 11), None);
+}
+
+#[test]
+fn test_ctrl_withdrawal_window() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 6), None);
+
+    // A new window starts once the delay has passed
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY * 2)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 6), None);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let window = contract.ctrl_withdrawal_window();
+    assert!(window.started_at == DAY * 2);
+    assert!(window.amount == U128(ONE_NEAR * 6));
+}
+
+#[test]
+#[should_panic(expected = "Withdrawals above the threshold must be queued through the timelock")]
+fn test_ctrl_withdrawal_panic_split() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 6), None);
+
+    // The second part pushes the total of the window above the threshold
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .block_timestamp(DAY * 2 - 1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 6), None);
+}
+
+#[test]
+#[should_panic(expected = "Mint info changes must be queued through the timelock")]
+fn test_nft_set_mint_info_panic_timelock() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_set_mint_info(MintInfo {
+        limit: 1,
+        public: 2,
+        listed: 3,
+    });
+}
+
+#[test]
+#[should_panic(expected = "Loosening the timelock must be queued through the timelock")]
+fn test_ctrl_set_timelock_panic_loosen() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_set_timelock(TimelockConfig {
+        delay: 0,
+        withdrawal_threshold: U128(0),
+    });
+}
+
+#[test]
+#[should_panic(expected = "Timelock action can not be executed before")]
+fn test_ctrl_execute_action_panic_eta() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let action_id = contract.ctrl_queue_action(TimelockAction::Withdrawal {
+        amount: U128(ONE_NEAR * 20),
+        receiver_id: acc_x.clone(),
+    });
+    contract.ctrl_execute_action(action_id);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or treasurer can withdrawal funds")]
+fn test_ctrl_queue_action_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _enable_timelock(&mut contract);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_queue_action(TimelockAction::Withdrawal {
+        amount: U128(ONE_NEAR * 20),
+        receiver_id: acc_a.clone(),
+    });
+}
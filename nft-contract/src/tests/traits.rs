@@ -0,0 +1,207 @@
+use super::*;
+
+#[test]
+fn test_nft_tokens_by_trait() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.a"),
+        r#"{"Background":"Void","Level":3}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.b"),
+        r#"{"Background":"Void"}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.c"),
+        r#"{"Background":"Red"}"#,
+        acc_a.clone(),
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let tokens = _token_lsit_to_map(contract.nft_tokens_by_trait(
+        "Background".to_string(),
+        "Void".to_string(),
+        None,
+        None,
+    ));
+    assert!(tokens.len() == 2);
+    assert!(tokens.contains_key("token.a"));
+    assert!(tokens.contains_key("token.b"));
+
+    // Non string values are matched by their JSON form
+    let tokens = contract.nft_tokens_by_trait("Level".to_string(), "3".to_string(), None, None);
+    assert!(tokens.len() == 1);
+    // The extra field keeps the attributes as they were registered
+    assert!(tokens[0].metadata.extra == Some(r#"{"Background":"Void","Level":3}"#.to_string()));
+
+    let tokens = contract.nft_tokens_by_trait(
+        "Background".to_string(),
+        "Void".to_string(),
+        Some(U128(1)),
+        Some(5),
+    );
+    assert!(tokens.len() == 1);
+    let tokens =
+        contract.nft_tokens_by_trait("Background".to_string(), "Blue".to_string(), None, None);
+    assert!(tokens.is_empty());
+
+    let counts = contract.nft_trait_counts();
+    assert!(counts["Background"]["Void"] == 2);
+    assert!(counts["Background"]["Red"] == 1);
+    assert!(counts["Level"]["3"] == 1);
+
+    let traits = contract.nft_token_traits("token.a".to_string());
+    assert!(
+        traits
+            == vec![
+                TokenTrait {
+                    trait_type: "Background".to_string(),
+                    value: "Void".to_string(),
+                },
+                TokenTrait {
+                    trait_type: "Level".to_string(),
+                    value: "3".to_string(),
+                },
+            ]
+    );
+}
+
+#[test]
+fn test_nft_traits_follow_updates() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.a"),
+        r#"{"Background":"Void","Level":3}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.b"),
+        r#"{"Background":"Void"}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.c"),
+        r#"{"Background":"Red"}"#,
+        acc_a.clone(),
+    );
+
+    // Evolving a token moves it to its new trait values
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_update_metadata(
+        "token.a".to_string(),
+        TokenMetadataUpdate {
+            title: None,
+            media: None,
+            media_hash: None,
+            attributes: Some(r#"{"Background":"Red"}"#.to_string()),
+        },
+    );
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(
+        contract.nft_token_traits("token.a".to_string())
+            == vec![TokenTrait {
+                trait_type: "Background".to_string(),
+                value: "Red".to_string(),
+            }]
+    );
+
+    // Burned tokens leave the index
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_burn("token.b".to_string());
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let counts = contract.nft_trait_counts();
+    assert!(counts.len() == 1);
+    assert!(counts["Background"].len() == 1);
+    assert!(counts["Background"]["Red"] == 2);
+    let tokens =
+        contract.nft_tokens_by_trait("Background".to_string(), "Void".to_string(), None, None);
+    assert!(tokens.is_empty());
+    assert!(contract.nft_token_traits("token.b".to_string()).is_empty());
+}
+
+#[test]
+fn test_ctrl_reindex_traits() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.a"),
+        r#"{"Background":"Void","Level":3}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.b"),
+        r#"{"Background":"Void"}"#,
+        acc_a.clone(),
+    );
+    _mint_token_with_attributes(
+        &mut contract,
+        String::from("token.c"),
+        r#"{"Background":"Red"}"#,
+        acc_a.clone(),
+    );
+
+    // Tokens minted before the upgrade are not indexed yet
+    contract.internal_unindex_traits(&"token.a".to_string());
+    contract.internal_unindex_traits(&"token.c".to_string());
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_trait_counts()["Background"]["Void"] == 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    assert!(contract.ctrl_reindex_traits(None, Some(2)) == 1);
+    assert!(contract.ctrl_reindex_traits(Some(U128(2)), None) == 1);
+    assert!(contract.ctrl_reindex_traits(None, None) == 0);
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let counts = contract.nft_trait_counts();
+    assert!(counts["Background"]["Void"] == 2);
+    assert!(counts["Background"]["Red"] == 1);
+    assert!(counts["Level"]["3"] == 1);
+}
+
+#[test]
+#[should_panic(expected = "Only owner or admin can reindex traits")]
+fn test_ctrl_reindex_traits_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_reindex_traits(None, None);
+}
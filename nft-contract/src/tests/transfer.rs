@@ -0,0 +1,166 @@
+use super::*;
+
+use crate::approval::NftApproval;
+use crate::enumeration::NftEnumeration;
+use near_sdk::Gas;
+
+#[test]
+fn test_nft_batch_transfer() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+    let tkn_c = String::from("token.c");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_c = AccountId::new_unchecked(String::from("account.c"));
+    let acc_m = AccountId::new_unchecked(String::from("account.market"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_b.clone(), acc_a.clone());
+    _mint_token(&mut contract, tkn_c.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_approve(tkn_c.clone(), acc_m.clone(), None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_batch_transfer(vec![
+        (acc_b.clone(), tkn_a.clone(), None),
+        (acc_b.clone(), tkn_b.clone(), None),
+        (acc_c.clone(), tkn_c.clone(), None),
+    ]);
+
+    // One event with the tokens grouped per owner pair
+    let logs = near_sdk::test_utils::get_logs();
+    assert!(logs.len() == 1);
+    assert!(logs[0].contains(r#""new_owner_id":"account.b","token_ids":["token.a","token.b"]"#));
+    assert!(logs[0].contains(r#""new_owner_id":"account.c","token_ids":["token.c"]"#));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    assert!(contract.nft_supply_for_owner(acc_a.clone()) == U128::from(0));
+    assert!(contract.nft_supply_for_owner(acc_b.clone()) == U128::from(2));
+    assert!(contract.nft_supply_for_owner(acc_c.clone()) == U128::from(1));
+}
+
+#[test]
+#[should_panic(expected = "Batch transfers are limited to 20 tokens")]
+fn test_nft_batch_transfer_panic_size() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    let transfers = (0..MAX_BATCH_TRANSFER + 1)
+        .map(|index| (acc_b.clone(), format!("token.{}", index), None))
+        .collect();
+    contract.nft_batch_transfer(transfers);
+}
+
+#[test]
+#[should_panic(expected = "Unauthorized transfer")]
+fn test_nft_batch_transfer_panic_access() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    _mint_token(&mut contract, tkn_a.clone(), acc_a.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_batch_transfer(vec![(acc_b.clone(), tkn_a.clone(), None)]);
+}
+
+#[test]
+fn test_nft_batch_transfer_gas() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_d = AccountId::new_unchecked(String::from("account.delegate"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    let token_ids: Vec<String> = (0..MAX_BATCH_TRANSFER)
+        .map(|index| format!("token.{}", index))
+        .collect();
+    for token_id in token_ids.iter() {
+        _mint_token(&mut contract, token_id.clone(), acc_a.clone());
+    }
+
+    // Every transfer has to checkpoint the balances and clear a delegation
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_take_snapshot();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR)
+        .is_view(false)
+        .build());
+    contract.nft_delegate(acc_d.clone(), token_ids.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .prepaid_gas(Gas(300_000_000_000_000))
+        .is_view(false)
+        .build());
+    contract.nft_batch_transfer(
+        token_ids
+            .iter()
+            .map(|token_id| (acc_b.clone(), token_id.clone(), None))
+            .collect(),
+    );
+    let used = env::used_gas();
+    // The limit assumes at most 9 TGas per transfer, a more expensive transfer has to lower it
+    let per_transfer = used.0 / MAX_BATCH_TRANSFER as u64;
+    assert!(
+        per_transfer <= 9_000_000_000_000,
+        "nft_batch_transfer used {} gas per transfer, MAX_BATCH_TRANSFER assumes at most 9 TGas",
+        per_transfer
+    );
+    assert!(
+        used < Gas(200_000_000_000_000),
+        "nft_batch_transfer of {} tokens used {} gas",
+        MAX_BATCH_TRANSFER,
+        used.0
+    );
+}
+
+#[test]
+#[should_panic(expected = "Batch transfer requires at least one token")]
+fn test_nft_batch_transfer_panic_empty() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_batch_transfer(vec![]);
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[test]
+fn test_ctrl_treasury() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_b = AccountId::new_unchecked(String::from("account.b"));
+    let acc_t = AccountId::new_unchecked(String::from("account.treasury"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(REG_COST)
+        .is_view(false)
+        .build());
+    let mut map = HashMap::new();
+    map.insert(
+        tkn_a.clone(),
+        TokenMetadata {
+            title: tkn_a.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+            description: None,
+            reference: None,
+            reference_hash: None,
+        },
+    );
+    contract.nft_register(map, None);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.nft_allow_minting(acc_a.clone(), 1);
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .attached_deposit(ONE_NEAR * 22)
+        .is_view(false)
+        .build());
+    contract.nft_mint();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_b.clone())
+        .attached_deposit(ONE_NEAR * 3)
+        .is_view(false)
+        .build());
+    contract.ctrl_donate();
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 10), Some(acc_t.clone()));
+
+    testing_env!(VMContextBuilder::new().is_view(true).build());
+    let treasury = contract.ctrl_treasury();
+    assert!(treasury.mint_revenue == U128(ONE_NEAR * 22));
+    assert!(treasury.donations == U128(ONE_NEAR * 3));
+    assert!(treasury.withdrawn == U128(ONE_NEAR * 10));
+}
+
+#[test]
+fn test_ctrl_withdrawable() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .account_balance(ONE_NEAR * 50)
+        .storage_usage(10_000)
+        .is_view(true)
+        .build());
+
+    // The storage staking and the reserve stay on the contract
+    let locked = 10_000 * env::storage_byte_cost();
+    assert!(contract.ctrl_withdrawable() == U128(ONE_NEAR * 50 - locked - STORAGE_RESERVE));
+}
+
+#[test]
+#[should_panic(expected = "Withdrawal exceeds the withdrawable balance")]
+fn test_ctrl_withdrawal_panic_reserve() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .account_balance(ONE_NEAR * 50)
+        .storage_usage(10_000)
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_withdrawal(U128(ONE_NEAR * 50), None);
+}
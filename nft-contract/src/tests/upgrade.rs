@@ -0,0 +1,257 @@
+use super::*;
+
+use near_sdk::borsh::BorshSerialize;
+use near_sdk::Gas;
+
+use crate::enumeration::NftEnumeration;
+
+fn _old_state(owner_id: AccountId, token_id: String, token_owner_id: AccountId) -> ContractV1 {
+    let mut old = ContractV1 {
+        owner_id,
+        metadata: LazyOption::new(
+            StorageKey::ContractMetadata.try_to_vec().unwrap(),
+            Some(&ContractMetadata {
+                spec: "nft-2.0.0".to_string(),
+                name: "Auras".to_string(),
+                symbol: "AURA".to_string(),
+                icon: None,
+                base_uri: None,
+                reference: None,
+                reference_hash: None,
+            }),
+        ),
+        mint_info: LazyOption::new(
+            StorageKey::ContractMintState.try_to_vec().unwrap(),
+            Some(&MintInfo {
+                limit: 5,
+                public: 0,
+                listed: 22,
+            }),
+        ),
+        mint_state_list: UnorderedMap::new(StorageKey::MintStateList.try_to_vec().unwrap()),
+        tokens_by_id: UnorderedMap::new(StorageKey::TokensById.try_to_vec().unwrap()),
+        meta_data_by_id: UnorderedMap::new(StorageKey::MetaDataById.try_to_vec().unwrap()),
+        tokens_per_owner: UnorderedMap::new(StorageKey::TokensPerOwner.try_to_vec().unwrap()),
+    };
+
+    old.meta_data_by_id.insert(
+        &token_id,
+        //the first version stored the metadata without a version tag
+        &VersionedTokenMetadata::V1(TokenMetadataV1 {
+            title: token_id.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+        }),
+    );
+    old.tokens_by_id.insert(
+        &token_id,
+        &Token {
+            owner_id: token_owner_id.clone(),
+            issued_at: 1,
+            next_approval_id: 0,
+            approved_account_ids: Default::default(),
+        },
+    );
+    let mut tokens_set = UnorderedSet::new(
+        StorageKey::TokenPerOwnerInner {
+            account_id_hash: crate::internal::hash_account_id(&token_owner_id),
+        }
+        .try_to_vec()
+        .unwrap(),
+    );
+    tokens_set.insert(&token_id);
+    old.tokens_per_owner.insert(&token_owner_id, &tokens_set);
+    old
+}
+
+#[test]
+fn test_ctrl_migrate_v1() {
+    let tkn_a = String::from("token.a");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.contract"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_c.clone())
+        .is_view(false)
+        .build());
+
+    // Write the state as the first deployed version did
+    let old = _old_state(acc_x.clone(), tkn_a.clone(), acc_a.clone());
+    env::storage_write(b"STATE", &old.try_to_vec().unwrap());
+
+    let contract = Contract::migrate();
+
+    assert!(contract.ctrl_state_version() == CONTRACT_STATE_VERSION);
+    assert!(contract.ctrl_owner() == acc_x.clone());
+    assert!(contract.ctrl_pending_owner().is_none());
+    assert!(contract.nft_mint_info().listed == 22);
+    assert!(contract.nft_total_supply() == U128::from(1));
+    assert!(contract.nft_supply_for_owner(acc_a.clone()) == U128::from(1));
+
+    let token = contract.nft_token(tkn_a.clone()).expect("must be set");
+    assert!(token.owner_id == acc_a.clone());
+    assert!(token.metadata.title.expect("must be set") == tkn_a.clone());
+    assert!(token.metadata.description.expect("must be set") == "An exchange of energies.");
+
+    // Tokens are paged in mint order once they are queued, by owner after a reindex
+    let mut contract = contract;
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    assert!(contract.ctrl_fill_mint_queue(None) == 1);
+    assert!(contract.ctrl_fill_mint_queue(None) == 0);
+
+    let page = contract.nft_tokens_by_cursor(None, None);
+    assert!(page.tokens.len() == 1);
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_a.clone(), None, None);
+    assert!(page.tokens.is_empty());
+
+    assert!(contract.ctrl_reindex_tokens(None, None) == 1);
+    assert!(contract.ctrl_reindex_tokens(None, None) == 0);
+
+    let page = contract.nft_tokens_for_owner_by_cursor(acc_a.clone(), None, None);
+    assert!(page.tokens.len() == 1);
+    assert!(page.tokens[0].token_id == tkn_a);
+}
+
+#[test]
+fn test_nft_tokens_by_cursor_before_reindex() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.contract"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_c.clone())
+        .is_view(false)
+        .build());
+
+    // Two tokens minted by the first version
+    let mut old = _old_state(acc_x.clone(), tkn_a.clone(), acc_a.clone());
+    old.meta_data_by_id.insert(
+        &tkn_b,
+        &VersionedTokenMetadata::V1(TokenMetadataV1 {
+            title: tkn_b.clone(),
+            media: "bb".to_string(),
+            media_hash: "cc".to_string(),
+            attributes: "dd".to_string(),
+        }),
+    );
+    old.tokens_by_id.insert(
+        &tkn_b,
+        &Token {
+            owner_id: acc_a.clone(),
+            issued_at: 1,
+            next_approval_id: 0,
+            approved_account_ids: Default::default(),
+        },
+    );
+    let mut tokens_set = old.tokens_per_owner.get(&acc_a).expect("must be set");
+    tokens_set.insert(&tkn_b);
+    old.tokens_per_owner.insert(&acc_a, &tokens_set);
+    env::storage_write(b"STATE", &old.try_to_vec().unwrap());
+
+    let mut contract = Contract::migrate();
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_x.clone())
+        .attached_deposit(1)
+        .is_view(false)
+        .build());
+    contract.ctrl_fill_mint_queue(None);
+
+    // The cursor of the first page works before the tokens are reindexed
+    let page = contract.nft_tokens_by_cursor(None, Some(1));
+    assert!(page.tokens.len() == 1);
+    assert!(page.tokens[0].token_id == tkn_a);
+    let page = contract.nft_tokens_by_cursor(page.next_cursor, Some(1));
+    assert!(page.tokens.len() == 1);
+    assert!(page.tokens[0].token_id == tkn_b);
+    assert!(page.next_cursor.is_none());
+}
+
+#[test]
+fn test_ctrl_migrate_current() {
+    let acc_c = AccountId::new_unchecked(String::from("account.contract"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_c.clone())
+        .is_view(false)
+        .build());
+
+    // A code only upgrade keeps the state as it is
+    let mut contract = Contract::ctrl_init_default(acc_x.clone());
+    contract.mint_paused = true;
+    env::storage_write(b"STATE", &contract.try_to_vec().unwrap());
+
+    let contract = Contract::migrate();
+
+    assert!(contract.ctrl_state_version() == CONTRACT_STATE_VERSION);
+    assert!(contract.ctrl_owner() == acc_x.clone());
+    assert!(contract.nft_mint_paused());
+}
+
+#[test]
+#[should_panic(expected = "The mint queue has to be filled after the upgrade")]
+fn test_nft_register_panic_mint_queue() {
+    let tkn_a = String::from("token.a");
+    let tkn_b = String::from("token.b");
+
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_c = AccountId::new_unchecked(String::from("account.contract"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    testing_env!(VMContextBuilder::new()
+        .current_account_id(acc_c.clone())
+        .predecessor_account_id(acc_c.clone())
+        .is_view(false)
+        .build());
+    let old = _old_state(acc_x.clone(), tkn_a.clone(), acc_a.clone());
+    env::storage_write(b"STATE", &old.try_to_vec().unwrap());
+    let mut contract = Contract::migrate();
+
+    // New tokens would be queued before the tokens of the first version
+    _register_tokens(&mut contract, vec![tkn_b.clone()]);
+}
+
+#[test]
+#[should_panic(expected = "Only owner can upgrade the contract")]
+fn test_ctrl_upgrade_panic_access() {
+    let acc_a = AccountId::new_unchecked(String::from("account.a"));
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_a.clone())
+        .is_view(false)
+        .build());
+    contract.ctrl_upgrade();
+}
+
+#[test]
+#[should_panic(expected = "Not enough gas attached to upgrade the contract")]
+fn test_ctrl_upgrade_panic_gas() {
+    let acc_x = AccountId::new_unchecked(String::from("account.x"));
+
+    let contract = Contract::ctrl_init_default(acc_x.clone());
+
+    testing_env!(VMContextBuilder::new()
+        .predecessor_account_id(acc_x.clone())
+        .prepaid_gas(Gas(5_000_000_000_000))
+        .is_view(false)
+        .build());
+    contract.ctrl_upgrade();
+}
@@ -0,0 +1,242 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// Delay and thresholds for the sensitive owner actions
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TimelockConfig {
+    //nanoseconds a queued action has to wait before it can be executed, 0 disables the timelock
+    pub delay: u64,
+    //withdrawals above this amount have to be queued while the timelock is enabled
+    pub withdrawal_threshold: U128,
+}
+
+/// Sensitive owner actions that can be queued in the timelock
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum TimelockAction {
+    SetMintInfo {
+        info: MintInfo,
+    },
+    Withdrawal {
+        amount: U128,
+        receiver_id: AccountId,
+    },
+    GrantRole {
+        account_id: AccountId,
+        role: Role,
+    },
+    SetMetadata {
+        metadata: ContractMetadata,
+    },
+    SetTimelock {
+        config: TimelockConfig,
+    },
+}
+
+impl TimelockAction {
+    //name of the action as it is used in the event logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimelockAction::SetMintInfo { .. } => "set_mint_info",
+            TimelockAction::Withdrawal { .. } => "withdrawal",
+            TimelockAction::GrantRole { .. } => "grant_role",
+            TimelockAction::SetMetadata { .. } => "set_metadata",
+            TimelockAction::SetTimelock { .. } => "set_timelock",
+        }
+    }
+}
+
+/// An action waiting in the timelock queue
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct PendingAction {
+    pub action_id: u64,
+    pub action: TimelockAction,
+    //account that queued the action
+    pub proposer_id: AccountId,
+    //earliest block timestamp the action can be executed at
+    pub eta: u64,
+}
+
+/*******************/
+/* Timelock Config */
+/*******************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_timelock(&self) -> TimelockConfig {
+        self.timelock.get().expect("Timelock config not found")
+    }
+
+    #[payable]
+    pub fn ctrl_set_timelock(&mut self, config: TimelockConfig) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can set the timelock");
+
+        //the timelock can be made stricter right away, loosening it has to wait for the current delay
+        let current = self.ctrl_timelock();
+        require!(
+            current.delay == 0
+                || (config.delay >= current.delay
+                    && config.withdrawal_threshold.0 <= current.withdrawal_threshold.0),
+            "Loosening the timelock must be queued through the timelock",
+        );
+        self.timelock.set(&config);
+    }
+}
+
+/******************/
+/* Timelock Queue */
+/******************/
+
+#[near_bindgen]
+impl Contract {
+    #[payable]
+    pub fn ctrl_queue_action(&mut self, action: TimelockAction) -> u64 {
+        //require that the caller attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //the caller needs the same access as for the direct call
+        self.internal_require_action_access(&action);
+
+        let action_id = self.timelock_next_id;
+        self.timelock_next_id += 1;
+
+        let pending = PendingAction {
+            action_id,
+            action,
+            proposer_id: env::predecessor_account_id(),
+            eta: env::block_timestamp() + self.ctrl_timelock().delay,
+        };
+        self.timelock_actions.insert(&action_id, &pending);
+
+        log_aura_event(EventLogVariant::TimelockQueue(vec![TimelockLog {
+            action_id,
+            action: pending.action.as_str().to_string(),
+            eta: pending.eta,
+        }]));
+
+        action_id
+    }
+
+    #[payable]
+    pub fn ctrl_execute_action(&mut self, action_id: u64) {
+        //require that the caller attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let pending = self
+            .timelock_actions
+            .get(&action_id)
+            .expect("Timelock action not found");
+        //the caller needs the same access as for the direct call
+        self.internal_require_action_access(&pending.action);
+        require!(
+            env::block_timestamp() >= pending.eta,
+            format!("Timelock action can not be executed before {}", pending.eta),
+        );
+        self.timelock_actions.remove(&action_id);
+
+        log_aura_event(EventLogVariant::TimelockExecute(vec![TimelockLog {
+            action_id,
+            action: pending.action.as_str().to_string(),
+            eta: pending.eta,
+        }]));
+
+        match pending.action {
+            TimelockAction::SetMintInfo { info } => self.internal_set_mint_info(info),
+            TimelockAction::Withdrawal {
+                amount,
+                receiver_id,
+            } => self.internal_withdrawal(amount.0, receiver_id),
+            TimelockAction::GrantRole { account_id, role } => {
+                self.internal_grant_role(account_id, role)
+            }
+            TimelockAction::SetMetadata { metadata } => self.internal_set_metadata(metadata),
+            TimelockAction::SetTimelock { config } => {
+                self.timelock.set(&config);
+            }
+        }
+    }
+
+    #[payable]
+    pub fn ctrl_cancel_action(&mut self, action_id: u64) {
+        //require that the caller attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+
+        let pending = self
+            .timelock_actions
+            .get(&action_id)
+            .expect("Timelock action not found");
+        //admins can cancel anything, the other roles only their own kind of action
+        if !self.internal_has_role(&env::predecessor_account_id(), Role::Admin) {
+            self.internal_require_action_access(&pending.action);
+        }
+        self.timelock_actions.remove(&action_id);
+
+        log_aura_event(EventLogVariant::TimelockCancel(vec![TimelockLog {
+            action_id,
+            action: pending.action.as_str().to_string(),
+            eta: pending.eta,
+        }]));
+    }
+}
+
+/************************/
+/* Timelock Enumeration */
+/************************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_pending_action(&self, action_id: u64) -> Option<PendingAction> {
+        self.timelock_actions.get(&action_id)
+    }
+
+    pub fn ctrl_pending_actions(
+        &self,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<PendingAction> {
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        self.timelock_actions
+            .values()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            .collect()
+    }
+}
+
+impl Contract {
+    //sensitive calls can only be made directly while the timelock is disabled
+    pub(crate) fn internal_require_no_timelock(&self, message: &str) {
+        require!(self.ctrl_timelock().delay == 0, message);
+    }
+
+    //require that the predecessor could have made the queued call directly
+    fn internal_require_action_access(&self, action: &TimelockAction) {
+        match action {
+            TimelockAction::SetMintInfo { .. } => {
+                self.internal_require_role(Role::Admin, "Only owner or admin can set mint info")
+            }
+            TimelockAction::Withdrawal { .. } => self.internal_require_role(
+                Role::Treasurer,
+                "Only owner or treasurer can withdrawal funds",
+            ),
+            TimelockAction::GrantRole { role, .. } => self.internal_require_role_manager(*role),
+            TimelockAction::SetMetadata { .. } => require!(
+                env::predecessor_account_id() == self.owner_id,
+                "Only owner can set metadata",
+            ),
+            TimelockAction::SetTimelock { .. } => {
+                self.internal_require_role(Role::Admin, "Only owner or admin can set the timelock")
+            }
+        }
+    }
+}
@@ -0,0 +1,177 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// A trait type and value pair read from the token attributes
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokenTrait {
+    pub trait_type: String,
+    //values that are not strings keep their JSON form, e.g. 3 becomes "3"
+    pub value: String,
+}
+
+//typed trait pairs of the stringified attributes, attributes that are not a JSON object have no traits
+pub(crate) fn _token_traits(attributes: &str) -> Vec<TokenTrait> {
+    let attributes: HashMap<String, serde_json::Value> =
+        serde_json::from_str(attributes).unwrap_or_default();
+    let mut traits: Vec<TokenTrait> = attributes
+        .into_iter()
+        .map(|(trait_type, value)| TokenTrait {
+            trait_type,
+            value: match value {
+                serde_json::Value::String(value) => value,
+                value => value.to_string(),
+            },
+        })
+        .collect();
+    //keep the order stable so the stored pairs do not depend on the hash map
+    traits.sort_by(|a, b| a.trait_type.cmp(&b.trait_type));
+    traits
+}
+
+/***************/
+/* Trait Query */
+/***************/
+
+#[near_bindgen]
+impl Contract {
+    //minted tokens that have the given trait value
+    pub fn nft_tokens_by_trait(
+        &self,
+        trait_type: String,
+        value: String,
+        from_index: Option<U128>,
+        limit: Option<u64>,
+    ) -> Vec<JsonToken> {
+        let tokens = match self.tokens_by_trait.get(&TokenTrait { trait_type, value }) {
+            Some(tokens) => tokens,
+            None => return vec![],
+        };
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        tokens
+            .iter()
+            //skip to the index we specified in the start variable
+            .skip(start as usize)
+            //take the first "limit" elements in the vector. If we didn't specify a limit, use 50
+            .take(limit.unwrap_or(50) as usize)
+            //we'll map the token IDs which are strings into Json Tokens
+            .map(|token_id| self.nft_token(token_id).unwrap())
+            .collect()
+    }
+
+    //number of minted tokens for every trait type and value
+    pub fn nft_trait_counts(&self) -> HashMap<String, HashMap<String, u64>> {
+        self.trait_counts.iter().collect()
+    }
+
+    //typed trait pairs of an indexed token
+    pub fn nft_token_traits(&self, token_id: TokenId) -> Vec<TokenTrait> {
+        self.traits_by_id.get(&token_id).unwrap_or_default()
+    }
+
+    /*
+        adds the minted tokens in the given range to the trait index.
+        tokens minted before the index existed are only found after this ran over them,
+        tokens that are already indexed are skipped. returns the number of tokens added.
+    */
+    #[payable]
+    pub fn ctrl_reindex_traits(&mut self, from_index: Option<U128>, limit: Option<u64>) -> u64 {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can reindex traits");
+
+        //where to start pagination - if we have a from_index, we'll use that - otherwise start from 0 index
+        let start = u128::from(from_index.unwrap_or(U128(0)));
+
+        let list: Vec<TokenId> = self
+            .tokens_by_id
+            .keys()
+            .skip(start as usize)
+            .take(limit.unwrap_or(50) as usize)
+            .collect();
+
+        let mut count = 0;
+        for token_id in list {
+            if self.traits_by_id.get(&token_id).is_none() {
+                self.internal_index_traits(&token_id);
+                count += 1;
+            }
+        }
+        count
+    }
+}
+
+impl Contract {
+    //adds the token to the trait index using its current attributes
+    pub(crate) fn internal_index_traits(&mut self, token_id: &TokenId) {
+        require!(
+            self.traits_by_id.get(token_id).is_none(),
+            "Token traits are already indexed",
+        );
+        let metadata = self
+            .internal_token_metadata(token_id)
+            .expect("metadata not found");
+        let traits = _token_traits(&metadata.attributes);
+
+        for token_trait in traits.iter() {
+            let mut tokens = self.tokens_by_trait.get(token_trait).unwrap_or_else(|| {
+                UnorderedSet::new(
+                    StorageKey::TokensByTraitInner {
+                        //we get a new unique prefix for the collection
+                        trait_hash: hash_token_trait(token_trait),
+                    }
+                    .try_to_vec()
+                    .unwrap(),
+                )
+            });
+            tokens.insert(token_id);
+            self.tokens_by_trait.insert(token_trait, &tokens);
+
+            let mut counts = self
+                .trait_counts
+                .get(&token_trait.trait_type)
+                .unwrap_or_default();
+            *counts.entry(token_trait.value.clone()).or_insert(0) += 1;
+            self.trait_counts.insert(&token_trait.trait_type, &counts);
+        }
+        self.traits_by_id.insert(token_id, &traits);
+    }
+
+    //removes the token from the trait index, tokens that were never indexed are ignored
+    pub(crate) fn internal_unindex_traits(&mut self, token_id: &TokenId) {
+        let traits = match self.traits_by_id.remove(token_id) {
+            Some(traits) => traits,
+            None => return,
+        };
+
+        for token_trait in traits.iter() {
+            if let Some(mut tokens) = self.tokens_by_trait.get(token_trait) {
+                tokens.remove(token_id);
+                if tokens.is_empty() {
+                    self.tokens_by_trait.remove(token_trait);
+                } else {
+                    self.tokens_by_trait.insert(token_trait, &tokens);
+                }
+            }
+
+            if let Some(mut counts) = self.trait_counts.get(&token_trait.trait_type) {
+                let count = counts.get(&token_trait.value).cloned().unwrap_or(0);
+                if count > 1 {
+                    counts.insert(token_trait.value.clone(), count - 1);
+                } else {
+                    counts.remove(&token_trait.value);
+                }
+                if counts.is_empty() {
+                    self.trait_counts.remove(&token_trait.trait_type);
+                } else {
+                    self.trait_counts.insert(&token_trait.trait_type, &counts);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,141 @@
+use near_sdk::require;
+
+use crate::*;
+
+/// NEAR kept aside on top of the storage staking so refunds and new writes keep working
+pub const STORAGE_RESERVE: Balance = ONE_NEAR;
+
+/// Running totals of the NEAR that entered and left the contract
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Treasury {
+    //deposits received for minting tokens
+    pub mint_revenue: U128,
+    //deposits received through ctrl_donate
+    pub donations: U128,
+    //total amount paid out through withdrawals
+    pub withdrawn: U128,
+    //storage balances held for the accounts, these can not be withdrawn
+    pub storage_deposits: U128,
+}
+
+impl Default for Treasury {
+    fn default() -> Self {
+        Self {
+            mint_revenue: U128(0),
+            donations: U128(0),
+            withdrawn: U128(0),
+            storage_deposits: U128(0),
+        }
+    }
+}
+
+/// Direct withdrawals made since the start of the current timelock delay
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+#[serde(crate = "near_sdk::serde")]
+pub struct WithdrawalWindow {
+    //block timestamp of the first withdrawal in the window
+    pub started_at: u64,
+    //total withdrawn without going through the timelock
+    pub amount: U128,
+}
+
+impl Default for WithdrawalWindow {
+    fn default() -> Self {
+        Self {
+            started_at: 0,
+            amount: U128(0),
+        }
+    }
+}
+
+/************/
+/* Treasury */
+/************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_treasury(&self) -> Treasury {
+        self.treasury.clone()
+    }
+
+    pub fn ctrl_withdrawal_window(&self) -> WithdrawalWindow {
+        self.withdrawal_window.clone()
+    }
+
+    //balance that can be withdrawn without touching the NEAR that backs the storage
+    pub fn ctrl_withdrawable(&self) -> U128 {
+        let locked = Balance::from(env::storage_usage()) * env::storage_byte_cost();
+        U128(
+            env::account_balance()
+                .saturating_sub(locked)
+                .saturating_sub(self.treasury.storage_deposits.0)
+                .saturating_sub(STORAGE_RESERVE),
+        )
+    }
+
+    #[payable]
+    pub fn ctrl_donate(&mut self) {
+        require!(env::attached_deposit() > 0, "Donation requires a deposit");
+        self.treasury.donations = U128(self.treasury.donations.0 + env::attached_deposit());
+    }
+
+    #[payable]
+    pub fn ctrl_withdrawal(&mut self, amount: U128, beneficiary_id: Option<AccountId>) {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or a treasurer
+        self.internal_require_role(
+            Role::Treasurer,
+            "Only owner or treasurer can withdrawal funds",
+        );
+        //withdrawals are added up over the delay so splitting a large amount does not skip the timelock
+        let timelock = self.ctrl_timelock();
+        let mut window = self.withdrawal_window.clone();
+        if env::block_timestamp() >= window.started_at.saturating_add(timelock.delay) {
+            window = WithdrawalWindow {
+                started_at: env::block_timestamp(),
+                amount: U128(0),
+            };
+        }
+        window.amount = U128(window.amount.0 + amount.0);
+        //large withdrawals have to wait in the timelock
+        if window.amount.0 > timelock.withdrawal_threshold.0 {
+            self.internal_require_no_timelock(
+                "Withdrawals above the threshold must be queued through the timelock",
+            );
+        }
+        self.withdrawal_window = window;
+        self.internal_withdrawal(
+            u128::from(amount),
+            beneficiary_id.unwrap_or_else(env::predecessor_account_id),
+        );
+    }
+}
+
+impl Contract {
+    pub(crate) fn internal_withdrawal(&mut self, amount: Balance, receiver_id: AccountId) {
+        //never pay out the balance that is needed for storage
+        let withdrawable = self.ctrl_withdrawable().0;
+        require!(
+            amount <= withdrawable,
+            format!(
+                "Withdrawal exceeds the withdrawable balance of {} yoctoNEAR",
+                withdrawable
+            ),
+        );
+        self.treasury.withdrawn = U128(self.treasury.withdrawn.0 + amount);
+
+        log_aura_event(EventLogVariant::Withdrawal(vec![WithdrawalLog {
+            receiver_id: receiver_id.to_string(),
+            amount: amount.to_string(),
+        }]));
+
+        Promise::new(receiver_id).transfer(amount);
+    }
+
+    //keep track of the NEAR received for a mint
+    pub(crate) fn internal_add_mint_revenue(&mut self, amount: Balance) {
+        self.treasury.mint_revenue = U128(self.treasury.mint_revenue.0 + amount);
+    }
+}
@@ -0,0 +1,211 @@
+use near_sdk::{require, Gas};
+
+use crate::*;
+
+/// Version of the state layout written by this code.
+/// Bump it whenever the fields of a released `Contract` change and keep the released layout
+/// as its own struct with a migration to the next version.
+pub const CONTRACT_STATE_VERSION: u16 = 2;
+
+const GAS_FOR_UPGRADE: Gas = Gas(10_000_000_000_000);
+const NO_DEPOSIT: Balance = 0;
+
+/// State layout of the first deployed contract, it did not carry a version yet
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContractV1 {
+    pub owner_id: AccountId,
+    pub metadata: LazyOption<ContractMetadata>,
+    pub mint_info: LazyOption<MintInfo>,
+    pub mint_state_list: UnorderedMap<AccountId, MintState>,
+    pub tokens_by_id: UnorderedMap<TokenId, Token>,
+    pub meta_data_by_id: UnorderedMap<TokenId, VersionedTokenMetadata>,
+    pub tokens_per_owner: UnorderedMap<AccountId, UnorderedSet<TokenId>>,
+}
+
+/*******************/
+/* Contract Update */
+/*******************/
+
+#[near_bindgen]
+impl Contract {
+    pub fn ctrl_state_version(&self) -> u16 {
+        self.state_version
+    }
+
+    /*
+        deploys the wasm passed as the raw input of the call and migrates the state.
+        the input is read directly so the code does not need to be serialized as json.
+    */
+    pub fn ctrl_upgrade(&self) -> Promise {
+        //require the the sender is the owner of the contract
+        require!(
+            env::predecessor_account_id() == self.owner_id,
+            "Only owner can upgrade the contract",
+        );
+        //the migration gets the gas that is left, it can not be negative
+        require!(
+            env::prepaid_gas() > env::used_gas() + GAS_FOR_UPGRADE,
+            "Not enough gas attached to upgrade the contract",
+        );
+        let code = env::input().expect("Contract code not found");
+
+        //deploy and migrate in the same batch so a failing migration reverts the new code
+        Promise::new(env::current_account_id())
+            .deploy_contract(code)
+            .function_call(
+                "migrate".to_string(),
+                Vec::new(),
+                NO_DEPOSIT,
+                env::prepaid_gas() - env::used_gas() - GAS_FOR_UPGRADE,
+            )
+    }
+
+    /*
+        reads the state written by an older version of the contract and converts it
+        to the current layout. the state is read raw so every known layout can be tried.
+    */
+    #[private]
+    #[init(ignore_state)]
+    pub fn migrate() -> Self {
+        let state = env::storage_read(b"STATE").expect("Contract state not found");
+
+        //the first layout has no version field, it only parses when every byte matches
+        if let Ok(old) = ContractV1::try_from_slice(&state) {
+            return Self::internal_migrate_v1(old);
+        }
+
+        let contract = Self::try_from_slice(&state).expect("Unknown contract state layout");
+        require!(
+            contract.state_version == CONTRACT_STATE_VERSION,
+            format!("Unknown contract state version {}", contract.state_version),
+        );
+        //the layout did not change, only the code was updated
+        contract
+    }
+
+    /*
+        adds the tokens registered before the upgrade to the mint queue, in the order the first
+        version minted them. registering, minting and burning wait until every token was added.
+        returns the number of tokens added.
+    */
+    #[payable]
+    pub fn ctrl_fill_mint_queue(&mut self, limit: Option<u64>) -> u64 {
+        //require that the owner attached 1 yoctoNEAR for security reasons
+        require_one_yocto();
+        //require the the sender is the owner or an admin
+        self.internal_require_role(Role::Admin, "Only owner or admin can fill the mint queue");
+
+        //continue after the tokens that are already queued
+        let start = self.mint_queue.len();
+        let count = self.mint_queue_pending.min(limit.unwrap_or(50));
+        let keys = self.meta_data_by_id.keys_as_vector();
+        for index in start..start + count {
+            let token_id = keys.get(index).expect("Token not found");
+            self.mint_queue.push(&token_id);
+        }
+        self.mint_queue_pending -= count;
+        count
+    }
+}
+
+impl Contract {
+    fn internal_migrate_v1(old: ContractV1) -> Self {
+        //the first version minted the registered tokens in the order of their keys, they are queued by ctrl_fill_mint_queue
+        let mint_queue_pending = old.meta_data_by_id.len();
+        let mint_queue_head = old.tokens_by_id.len();
+
+        Self {
+            state_version: CONTRACT_STATE_VERSION,
+
+            //keep every collection of the old layout
+            owner_id: old.owner_id,
+            metadata: old.metadata,
+            mint_info: old.mint_info,
+            mint_state_list: old.mint_state_list,
+            tokens_by_id: old.tokens_by_id,
+            meta_data_by_id: old.meta_data_by_id,
+            tokens_per_owner: old.tokens_per_owner,
+            //the old tokens are indexed by ctrl_reindex_tokens
+            position_by_id: LookupMap::new(StorageKey::PositionById.try_to_vec().unwrap()),
+            owner_token_index: LookupMap::new(StorageKey::OwnerTokenIndex.try_to_vec().unwrap()),
+            mint_queue: Vector::new(StorageKey::MintQueue.try_to_vec().unwrap()),
+            mint_queue_head,
+            mint_queue_pending,
+
+            //initialize everything that was added since
+            pending_owner_id: None,
+            royalty_account_id: None,
+
+            roles: UnorderedMap::new(StorageKey::Roles.try_to_vec().unwrap()),
+            mint_paused: false,
+
+            timelock: LazyOption::new(
+                StorageKey::Timelock.try_to_vec().unwrap(),
+                Some(&TimelockConfig {
+                    delay: 0,
+                    withdrawal_threshold: U128(0),
+                }),
+            ),
+            timelock_actions: UnorderedMap::new(StorageKey::TimelockActions.try_to_vec().unwrap()),
+            timelock_next_id: 0,
+            withdrawal_window: WithdrawalWindow::default(),
+
+            treasury: Treasury::default(),
+
+            royalties: LazyOption::new(StorageKey::Royalties.try_to_vec().unwrap(), None),
+            token_royalties: UnorderedMap::new(StorageKey::TokenRoyalties.try_to_vec().unwrap()),
+            max_token_royalty: 0,
+
+            sales_by_id: UnorderedMap::new(StorageKey::SalesById.try_to_vec().unwrap()),
+            sale_stats: SaleStats::default(),
+
+            royalty_enforced: false,
+            marketplaces: UnorderedSet::new(StorageKey::Marketplaces.try_to_vec().unwrap()),
+            min_sale_price: U128(0),
+            gifts_by_id: LookupMap::new(StorageKey::GiftsById.try_to_vec().unwrap()),
+
+            burn_policy: BurnPolicy::Keep,
+
+            staking: LazyOption::new(
+                StorageKey::Staking.try_to_vec().unwrap(),
+                Some(&StakingConfig {
+                    token_rate: 0,
+                    trait_rates: HashMap::new(),
+                }),
+            ),
+            stakes_by_id: UnorderedMap::new(StorageKey::StakesById.try_to_vec().unwrap()),
+            points_by_owner: LookupMap::new(StorageKey::PointsByOwner.try_to_vec().unwrap()),
+
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+
+            updated_at_by_id: LookupMap::new(StorageKey::UpdatedAtById.try_to_vec().unwrap()),
+
+            traits_by_id: LookupMap::new(StorageKey::TraitsById.try_to_vec().unwrap()),
+            tokens_by_trait: LookupMap::new(StorageKey::TokensByTrait.try_to_vec().unwrap()),
+            trait_counts: UnorderedMap::new(StorageKey::TraitCounts.try_to_vec().unwrap()),
+
+            rarity_progress: RarityProgress::default(),
+            trait_frequencies: UnorderedMap::new(
+                StorageKey::TraitFrequencies.try_to_vec().unwrap(),
+            ),
+            rarity_by_id: LookupMap::new(StorageKey::RarityById.try_to_vec().unwrap()),
+            rarity_ranking: TreeMap::new(StorageKey::RarityRanking.try_to_vec().unwrap()),
+
+            snapshots: Vector::new(StorageKey::Snapshots.try_to_vec().unwrap()),
+            balance_checkpoints: LookupMap::new(
+                StorageKey::BalanceCheckpoints.try_to_vec().unwrap(),
+            ),
+            past_holders: UnorderedSet::new(StorageKey::PastHolders.try_to_vec().unwrap()),
+
+            proposals: Vector::new(StorageKey::Proposals.try_to_vec().unwrap()),
+            votes_by_token: LookupMap::new(StorageKey::VotesByToken.try_to_vec().unwrap()),
+            proposal_threshold: 1,
+
+            delegate_by_id: LookupMap::new(StorageKey::DelegateById.try_to_vec().unwrap()),
+            delegators_by_account: LookupMap::new(
+                StorageKey::DelegatorsByAccount.try_to_vec().unwrap(),
+            ),
+            delegated_votes: LookupMap::new(StorageKey::DelegatedVotes.try_to_vec().unwrap()),
+        }
+    }
+}
@@ -2,7 +2,7 @@ const fs = require('fs');
 const { exec } = require("child_process");
 
 // The contract call to pre-mint / register tokens
-// nft_register(& mut self, token_list: HashMap < String, TokenMetadata >)
+// nft_register(& mut self, token_list: HashMap < String, TokenMetadata >, royalties: Option < HashMap < String, HashMap < AccountId, u32 > > >)
 
 var count = 0;
 var batchIdx = 0;